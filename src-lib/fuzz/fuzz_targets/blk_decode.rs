@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use theist::sprite::blk;
+
+fuzz_target!(|data: &[u8]| {
+	let _ = blk::decode(data);
+	let _ = blk::decode_background(data);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use theist::sprite::c16;
+
+fuzz_target!(|data: &[u8]| {
+	let _ = c16::decode(data);
+});
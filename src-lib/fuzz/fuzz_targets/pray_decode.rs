@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use theist::format::pray;
+
+fuzz_target!(|data: &[u8]| {
+	let _ = pray::decode(data);
+	let _ = pray::decode_with_layout(data);
+});
@@ -0,0 +1,419 @@
+use std::{
+	fs,
+	env,
+	error::Error,
+	path::{ Path, PathBuf },
+	ffi::OsStr,
+	process::ExitCode
+};
+
+use theist::format::pray::{ Block, Compression, encode, encode_in_order, decode, decode_with_layout, split_blocks, CompressionOptions };
+use theist::format::pray_source;
+use theist::format::cob;
+use theist::format::agent_block::GameSupport;
+use theist::format::cp1252;
+use theist::format::file_block::{ File, SUPPORTED_EXTENSIONS, sort_dependencies, file_path_in };
+use theist::sprite::{ blk, c16, s16, image_error, export_sprite, export_sprite_sheet, export_animation, export_background, import_sprite, sort_frame_paths, EncodeOptions, PixelFormat, Dithering, SheetLayout };
+use theist::sprite::animation::FRAME_DELAY_MS;
+
+static USAGE: &str = "Usage: theist-cli <command> [arguments]
+
+Commands:
+  info <agent>                                  Show the tags in an agent file
+  list <agent>                                  List the dependencies in an agent file
+  extract <agent> [<file>...] [-o <dir>]        Extract dependencies (all of them if none are named)
+  build <source.txt> [-o <agent>]               Build an agent from a PRAYsource file
+  add-dependency <agent> <file>... [-t <tag>]   Add files to an agent as dependencies of a tag
+  convert <input> <output>                      Convert a sprite to PNG, or an agent to PRAYsource
+  convert <frame.png>... <sprite.c16>           Build a C16 or S16 sprite from PNG frames
+  convert <background.png> <background.blk>     Build a BLK background from one large PNG
+  convert <sprite.c16> <animation.gif>          Export a sprite as an animated GIF
+  convert <object.cob> <agent>                  Convert a C1 or C2 COB to a DS agent
+
+Sprite options:
+  --555                                         Use 555 pixels instead of 565
+  --dither <none|ordered|floyd-steinberg>       Dither colors when reducing them to 16 bits
+  --background                                  Export a BLK as one stitched image instead of tiles
+  --sheet <horizontal|grid>                     Export a sprite as one sheet PNG with a JSON manifest
+  --animated                                    Export a sprite as an animated PNG
+  --delay <ms>                                  Time between frames of an animation (default 50)";
+
+fn main() -> ExitCode {
+	let args: Vec<String> = env::args().skip(1).collect();
+	let result = match args.first().map(|a| a.as_str()) {
+		Some("info") => info(&args[1..]),
+		Some("list") => list(&args[1..]),
+		Some("extract") => extract(&args[1..]),
+		Some("build") => build(&args[1..]),
+		Some("add-dependency") => add_dependency(&args[1..]),
+		Some("convert") => convert(&args[1..]),
+		Some("help") | Some("-h") | Some("--help") => {
+			println!("{}", USAGE);
+			Ok(())
+		}
+		_ => Err(USAGE.into())
+	};
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(why) => {
+			eprintln!("{}", why);
+			ExitCode::FAILURE
+		}
+	}
+}
+
+struct Args {
+	paths: Vec<String>,
+	output: Option<String>,
+	tag: Option<String>,
+	encode_options: EncodeOptions,
+	background: bool,
+	sheet: Option<SheetLayout>,
+	animated: bool,
+	frame_delay: u32
+}
+
+fn parse_args(args: &[String]) -> Result<Args, Box<dyn Error>> {
+	let mut parsed_args = Args {
+		paths: Vec::new(),
+		output: None,
+		tag: None,
+		encode_options: EncodeOptions::default(),
+		background: false,
+		sheet: None,
+		animated: false,
+		frame_delay: FRAME_DELAY_MS
+	};
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-o" | "--output" => {
+				parsed_args.output = Some(args.next().ok_or("Missing path after -o")?.clone());
+			}
+			"-t" | "--tag" => {
+				parsed_args.tag = Some(args.next().ok_or("Missing tag name after -t")?.clone());
+			}
+			"--555" => {
+				parsed_args.encode_options.pixel_format = PixelFormat::Rgb555;
+			}
+			"--background" => {
+				parsed_args.background = true;
+			}
+			"--sheet" => {
+				parsed_args.sheet = match args.next().map(|a| a.as_str()) {
+					Some("horizontal") => Some(SheetLayout::Horizontal),
+					Some("grid") => Some(SheetLayout::Grid),
+					_ => return Err("Expected horizontal or grid after --sheet".into())
+				};
+			}
+			"--animated" => {
+				parsed_args.animated = true;
+			}
+			"--delay" => {
+				parsed_args.frame_delay = args.next().and_then(|a| a.parse().ok()).ok_or("Expected a number of milliseconds after --delay")?;
+			}
+			"--dither" => {
+				parsed_args.encode_options.dithering = match args.next().map(|a| a.as_str()) {
+					Some("none") => Dithering::None,
+					Some("ordered") => Dithering::Ordered,
+					Some("floyd-steinberg") => Dithering::FloydSteinberg,
+					_ => return Err("Expected none, ordered or floyd-steinberg after --dither".into())
+				};
+			}
+			_ => parsed_args.paths.push(arg.clone())
+		}
+	}
+	Ok(parsed_args)
+}
+
+fn read_agent(path: &str) -> Result<(Vec<Block>, Vec<File>), Box<dyn Error>> {
+	let bytes = fs::read(path).map_err(|why| format!("Unable to read \"{}\": {}", path, why))?;
+	Ok(split_blocks(decode(&bytes)?))
+}
+
+fn info(args: &[String]) -> Result<(), Box<dyn Error>> {
+	let args = parse_args(args)?;
+	let path = args.paths.first().ok_or(USAGE)?;
+	let (tags, dependencies) = read_agent(path)?;
+
+	for tag in &tags {
+		match tag {
+			Block::Agent(t) => {
+				println!("Agent \"{}\" ({})", t.name, match t.game_support {
+					GameSupport::Creatures3 => "Creatures 3",
+					GameSupport::DockingStation => "Docking Station"
+				});
+				println!("  Animation: {} \"{}\" from image {}", t.animation_file, t.animation_string, t.sprite_first_image);
+				println!("  Dependencies: {}", t.dependencies.join(", "));
+			}
+			Block::Egg(t) => {
+				println!("Egg \"{}\"", t.name);
+				println!("  Genetics: {} (mother: {}, father: {})", t.genetics_file, t.genetics_file_mother, t.genetics_file_father);
+				println!("  Sprites: {} (male), {} (female)", t.sprite_file_male, t.sprite_file_female);
+				println!("  Dependencies: {}", t.dependencies.join(", "));
+			}
+			Block::GardenBox(t) => {
+				println!("Garden Box \"{}\" (category {})", t.name, t.category);
+				println!("  Animation: {} from image {}", t.animation_file, t.sprite_first_image);
+				println!("  Dependencies: {}", t.dependencies.join(", "));
+			}
+			Block::Export(t) => {
+				println!("Exported creature \"{}\" ({})", t.name, t.creature_name);
+				println!("  From world: {} ({})", t.world_name, t.world_uid);
+			}
+			Block::History(t) => {
+				println!("History \"{}\" ({} events)", t.name, t.events.len());
+			}
+			Block::CreatureData(t) => {
+				println!("Creature \"{}\" ({} bytes)", t.name, t.data.len());
+			}
+			Block::Photo(t) => {
+				println!("Photo \"{}\" ({} bytes)", t.name, t.data.len());
+			}
+			Block::Generic(t) => {
+				println!("{} \"{}\" ({} bytes)", t.id, t.name, t.data.len());
+				if let Some(values) = &t.values {
+					for (key, value) in &values.int_values {
+						println!("  {}: {}", key, value);
+					}
+					for (key, value) in &values.str_values {
+						println!("  {}: \"{}\"", key, value);
+					}
+				}
+			}
+			Block::File(_) => {}
+		}
+	}
+	println!("{} tags, {} dependencies", tags.len(), dependencies.len());
+
+	Ok(())
+}
+
+fn list(args: &[String]) -> Result<(), Box<dyn Error>> {
+	let args = parse_args(args)?;
+	let path = args.paths.first().ok_or(USAGE)?;
+	let (_, dependencies) = read_agent(path)?;
+	for dependency in dependencies {
+		println!("{}\t{}", dependency.filename(), dependency.data.len());
+	}
+	Ok(())
+}
+
+fn extract(args: &[String]) -> Result<(), Box<dyn Error>> {
+	let args = parse_args(args)?;
+	let path = args.paths.first().ok_or(USAGE)?;
+	let file_names = &args.paths[1..];
+	let output_dir = PathBuf::from(args.output.unwrap_or(".".to_string()));
+	let (_, dependencies) = read_agent(path)?;
+
+	for file_name in file_names {
+		if !dependencies.iter().any(|d| d.filename() == *file_name) {
+			return Err(format!("\"{}\" is not in the agent", file_name).into());
+		}
+	}
+
+	let dependencies: Vec<File> = dependencies.into_iter().filter(|d| file_names.is_empty() || file_names.contains(&d.filename())).collect();
+	let file_paths = dependencies.iter().map(|d| file_path_in(&output_dir, &d.filename())).collect::<Result<Vec<PathBuf>, _>>()?;
+
+	fs::create_dir_all(&output_dir)?;
+	for (dependency, file_path) in dependencies.iter().zip(&file_paths) {
+		fs::write(file_path, &dependency.data)?;
+		println!("{}", dependency.filename());
+	}
+
+	Ok(())
+}
+
+fn build(args: &[String]) -> Result<(), Box<dyn Error>> {
+	let args = parse_args(args)?;
+	let path = args.paths.first().ok_or(USAGE)?;
+	let output = match args.output {
+		Some(output) => PathBuf::from(output),
+		None => Path::new(path).with_extension("agents")
+	};
+	build_agent(Path::new(path), &output)
+}
+
+fn build_agent(source_path: &Path, output_path: &Path) -> Result<(), Box<dyn Error>> {
+	let text = pray_source::decode_text(&fs::read(source_path)?);
+	let root_path = source_path.parent().unwrap_or(Path::new(""));
+	let (tags, dependencies) = split_blocks(pray_source::decode(&text, root_path)?);
+	fs::write(output_path, encode(&tags, &dependencies)?)?;
+	println!("{}", output_path.display());
+	Ok(())
+}
+
+fn add_dependency(args: &[String]) -> Result<(), Box<dyn Error>> {
+	let args = parse_args(args)?;
+	let path = args.paths.first().ok_or(USAGE)?;
+	let file_paths = &args.paths[1..];
+	if file_paths.is_empty() {
+		return Err(USAGE.into());
+	}
+	let bytes = fs::read(path).map_err(|why| format!("Unable to read \"{}\": {}", path, why))?;
+	let (blocks, layout) = decode_with_layout(&bytes)?;
+	let (mut tags, mut dependencies) = split_blocks(blocks);
+
+	let tag_index = match &args.tag {
+		Some(tag_name) => Some(tags.iter().position(|t| tag_name_of(t) == tag_name)
+			.ok_or(format!("There is no tag named \"{}\"", tag_name))?),
+		None => if tags.is_empty() { None } else { Some(0) }
+	};
+
+	for file_path in file_paths {
+		let file_path = Path::new(file_path);
+		let extension = file_path.extension().unwrap_or(OsStr::new("")).to_str().unwrap_or("").to_ascii_lowercase();
+		let name = file_path.file_stem().unwrap_or(OsStr::new("")).to_str().unwrap_or("");
+		let dependency_name = format!("{}.{}", name, extension);
+		if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+			return Err(format!("\"{}\" is not a supported file type", dependency_name).into());
+		}
+
+		let data = fs::read(file_path)?;
+		match dependencies.iter_mut().find(|d| d.filename() == dependency_name) {
+			Some(dependency) => { dependency.data = data; }
+			None => {
+				dependencies.push(File {
+					name: name.to_string(),
+					extension: extension.to_string(),
+					data,
+					is_checked: false,
+					compression: Compression::Auto
+				});
+			}
+		}
+
+		if let Some(tag_index) = tag_index {
+			let tag_dependencies = match &mut tags[tag_index] {
+				Block::Agent(t) => Some(&mut t.dependencies),
+				Block::Egg(t) => Some(&mut t.dependencies),
+				Block::GardenBox(t) => Some(&mut t.dependencies),
+				_ => None
+			};
+			if let Some(tag_dependencies) = tag_dependencies {
+				if !tag_dependencies.contains(&dependency_name) {
+					tag_dependencies.push(dependency_name);
+				}
+			}
+		}
+	}
+
+	sort_dependencies(&mut dependencies);
+	let output = args.output.unwrap_or(path.clone());
+	// the new files go after the blocks that were already there
+	fs::write(&output, encode_in_order(&tags, &dependencies, &CompressionOptions::default(), &layout.order())?)?;
+	println!("{}", output);
+
+	Ok(())
+}
+
+fn tag_name_of(tag: &Block) -> &str {
+	match tag {
+		Block::Agent(t) => &t.name,
+		Block::Egg(t) => &t.name,
+		Block::GardenBox(t) => &t.name,
+		Block::Export(t) => &t.name,
+		Block::History(t) => &t.name,
+		Block::CreatureData(t) => &t.name,
+		Block::Photo(t) => &t.name,
+		Block::Generic(t) => &t.name,
+		Block::File(f) => &f.name
+	}
+}
+
+fn read_file(path: &Path, extension: &str) -> Result<File, Box<dyn Error>> {
+	Ok(File {
+		name: path.file_stem().unwrap_or(OsStr::new("")).to_str().unwrap_or("").to_string(),
+		extension: extension.to_string(),
+		data: fs::read(path)?,
+		is_checked: false,
+		compression: Compression::Auto
+	})
+}
+
+fn convert(args: &[String]) -> Result<(), Box<dyn Error>> {
+	let mut args = parse_args(args)?;
+	if args.output.is_none() && args.paths.len() > 1 {
+		args.output = args.paths.pop();
+	}
+	let (input, output) = match (args.paths.first(), args.output.as_ref()) {
+		(Some(input), Some(output)) => (Path::new(input), Path::new(output)),
+		_ => return Err(USAGE.into())
+	};
+	let input_extension = input.extension().unwrap_or(OsStr::new("")).to_str().unwrap_or("").to_ascii_lowercase();
+	let output_extension = output.extension().unwrap_or(OsStr::new("")).to_str().unwrap_or("").to_ascii_lowercase();
+
+	match (input_extension.as_str(), output_extension.as_str()) {
+		("blk", "png") if args.background => {
+			let file = read_file(input, &input_extension)?;
+			export_background(&file, output)?;
+			println!("{}", output.display());
+		}
+
+		("c16" | "s16" | "blk", "png") if args.sheet.is_some() || args.animated => {
+			let file = read_file(input, &input_extension)?;
+			match args.sheet {
+				Some(layout) => export_sprite_sheet(&file, output, &[], layout)?,
+				None => export_animation(&file, output, &[], args.frame_delay)?
+			}
+			println!("{}", output.display());
+		}
+
+		("c16" | "s16" | "blk", "gif") => {
+			let file = read_file(input, &input_extension)?;
+			export_animation(&file, output, &[], args.frame_delay)?;
+			println!("{}", output.display());
+		}
+
+		("c16" | "s16" | "blk", "png") => {
+			let file = read_file(input, &input_extension)?;
+			let frame_count = match input_extension.as_str() {
+				"c16" => c16::decode(&file.data)?.len(),
+				"s16" => s16::decode(&file.data)?.len(),
+				"blk" => blk::decode(&file.data)?.len(),
+				_ => return Err(image_error().into())
+			};
+			let frame_indexes: Vec<usize> = (0..frame_count).collect();
+			export_sprite(&file, output, &frame_indexes)?;
+			println!("{} frames", frame_count);
+		}
+
+		("png", "c16" | "s16" | "blk") => {
+			let mut frame_paths: Vec<PathBuf> = args.paths.iter().map(PathBuf::from).collect();
+			sort_frame_paths(&mut frame_paths);
+			let name = output.file_stem().unwrap_or(OsStr::new("")).to_str().unwrap_or("");
+			let file = import_sprite(name, &output_extension, &frame_paths, &args.encode_options)?;
+			fs::write(output, &file.data)?;
+			println!("{} frames", frame_paths.len());
+		}
+
+		("agent" | "agents", "txt") => {
+			let (tags, dependencies) = read_agent(input.to_str().unwrap_or(""))?;
+			let (text, files) = pray_source::encode(&tags, &dependencies)?;
+			let root_path = output.parent().unwrap_or(Path::new(""));
+			let file_paths = files.iter().map(|f| file_path_in(root_path, &f.filename())).collect::<Result<Vec<PathBuf>, _>>()?;
+			fs::write(output, cp1252::encode(&text))?;
+			for (file, file_path) in files.iter().zip(&file_paths) {
+				fs::write(file_path, &file.data)?;
+			}
+			println!("{}", output.display());
+		}
+
+		("txt", "agent" | "agents") => {
+			build_agent(input, output)?;
+		}
+
+		("cob", "agent" | "agents") => {
+			let cob = cob::decode(&fs::read(input)?)?;
+			let (tags, dependencies) = split_blocks(cob::convert_to_agent(&cob)?);
+			fs::write(output, encode(&tags, &dependencies)?)?;
+			println!("{}", output.display());
+		}
+
+		_ => {
+			return Err(format!("Unable to convert from .{} to .{}", input_extension, output_extension).into());
+		}
+	}
+
+	Ok(())
+}
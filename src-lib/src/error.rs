@@ -0,0 +1,76 @@
+use std::{ fmt, io };
+
+#[derive(Debug)]
+pub enum Error {
+	Io(io::Error),
+	Image(image::ImageError),
+	UnexpectedEnd(&'static str),
+	InvalidSprite(String),
+	InvalidAnimation(String),
+	InvalidGenome(String),
+	InvalidSound(String),
+	InvalidCob(String),
+	InvalidHistory(String),
+	TooLarge(String),
+	UnsafeFileName(String),
+	MissingFile { file_name: String, source: io::Error },
+	Syntax { line: usize, message: String }
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Io(why) => { write!(f, "{}", why) }
+			Error::Image(why) => { write!(f, "{}", why) }
+			Error::UnexpectedEnd(what) => { write!(f, "File ends in the middle of {}", what) }
+			Error::InvalidSprite(message) => { write!(f, "{}", message) }
+			Error::InvalidAnimation(message) => { write!(f, "Invalid animation string: {}", message) }
+			Error::InvalidGenome(message) => { write!(f, "{}", message) }
+			Error::InvalidSound(message) => { write!(f, "{}", message) }
+			Error::InvalidCob(message) => { write!(f, "{}", message) }
+			Error::InvalidHistory(message) => { write!(f, "{}", message) }
+			Error::TooLarge(message) => { write!(f, "{}", message) }
+			Error::UnsafeFileName(file_name) => { write!(f, "\"{}\" isn't a plain file name, so it won't be written", file_name) }
+			Error::MissingFile { file_name, source } => { write!(f, "Unable to read \"{}\": {}", file_name, source) }
+			Error::Syntax { line, message } => { write!(f, "Line {}: {}", line, message) }
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Io(why) => Some(why),
+			Error::Image(why) => Some(why),
+			Error::MissingFile { source, .. } => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<io::Error> for Error {
+	fn from(why: io::Error) -> Self {
+		Error::Io(why)
+	}
+}
+
+impl From<png::EncodingError> for Error {
+	fn from(why: png::EncodingError) -> Self {
+		match why {
+			png::EncodingError::IoError(why) => Error::Io(why),
+			_ => Error::Io(io::Error::new(io::ErrorKind::Other, why))
+		}
+	}
+}
+
+impl From<serde_json::Error> for Error {
+	fn from(why: serde_json::Error) -> Self {
+		Error::Io(why.into())
+	}
+}
+
+impl From<image::ImageError> for Error {
+	fn from(why: image::ImageError) -> Self {
+		Error::Image(why)
+	}
+}
@@ -0,0 +1,19 @@
+pub mod pray;
+pub mod pray_source;
+pub mod caos;
+pub mod catalogue;
+pub mod genome;
+pub mod gene_notes;
+pub mod attachment;
+pub mod wav;
+pub mod mng;
+pub mod cob;
+pub mod discovery;
+pub mod validation;
+pub mod cp1252;
+pub mod agent_block;
+pub mod egg_block;
+pub mod gb_block;
+pub mod creature_block;
+pub mod file_block;
+pub mod generic_block;
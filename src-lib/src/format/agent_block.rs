@@ -1,16 +1,22 @@
-use std::{ str, error::Error };
-use bytes::{ Bytes, BytesMut };
+use bytes::Bytes;
 
+use crate::error::Error;
+use super::cp1252;
 use super::file_block::File;
 use super::pray::{
 	Tag,
 	Block,
-	BlockHeader,
+	Compression,
+	CompressionOptions,
 	read_tag_block,
-	write_block_header,
+	write_block,
 	write_tag_block,
 	get_dependencies,
 	write_dependencies,
+	write_extra_values,
+	restore_key_order,
+	is_dependency_key,
+	is_script_key,
 	file_stem
 };
 
@@ -27,6 +33,11 @@ pub struct Agent {
 	pub sprite_first_image: u32,
 	pub remove_script: String,
 	pub dependencies: Vec<String>,
+	pub extra_int_values: Vec<(String, u32)>,
+	pub extra_str_values: Vec<(String, String)>,
+	#[serde(skip)]
+	pub key_order: Vec<String>,
+	pub compression: Compression
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -57,7 +68,7 @@ pub enum GameSupport {
 	DockingStation
 }
 
-pub fn read_agent_block(buffer: &mut Bytes, name: &str, game_support: GameSupport) -> Result<Vec<Block>, Box<dyn Error>> {
+pub fn read_agent_block(buffer: &mut Bytes, name: &str, game_support: GameSupport, compression: Compression) -> Result<Vec<Block>, Error> {
 	let tag = read_tag_block(buffer)?;
 	let mut agent_blocks: Vec<Block> = Vec::new();
 
@@ -70,24 +81,45 @@ pub fn read_agent_block(buffer: &mut Bytes, name: &str, game_support: GameSuppor
 		agent_blocks.push(script_block);
 	}
 
-	agent_blocks.push(Block::Agent(Agent {
+	let mut agent = Agent {
 		game_support,
 		name: name.to_string(),
 		descriptions: get_descriptions(&tag),
-		bioenergy: *tag.int_values.get("Agent Bioenergy Value").unwrap_or(&0) as u32,
-		web_label: tag.str_values.get("Web Label").unwrap_or(&String::new()).clone(),
-		web_url: tag.str_values.get("Web URL").unwrap_or(&String::new()).clone(),
-		animation_file: tag.str_values.get("Agent Animation File").unwrap_or(&String::new()).clone(),
-		animation_string: tag.str_values.get("Agent Animation String").unwrap_or(&String::new()).clone(),
-		sprite_first_image: *tag.int_values.get("Agent Sprite First Image").unwrap_or(&0) as u32,
-		remove_script: tag.str_values.get("Remove script").unwrap_or(&String::new()).clone(),
-		dependencies
-	}));
+		bioenergy: tag.get_int("Agent Bioenergy Value").unwrap_or(0),
+		web_label: tag.get_str("Web Label").cloned().unwrap_or_default(),
+		web_url: tag.get_str("Web URL").cloned().unwrap_or_default(),
+		animation_file: tag.get_str("Agent Animation File").cloned().unwrap_or_default(),
+		animation_string: tag.get_str("Agent Animation String").cloned().unwrap_or_default(),
+		sprite_first_image: tag.get_int("Agent Sprite First Image").unwrap_or(0),
+		remove_script: tag.get_str("Remove script").cloned().unwrap_or_default(),
+		dependencies,
+		extra_int_values: Vec::new(),
+		extra_str_values: Vec::new(),
+		key_order: tag.key_order(),
+		compression
+	};
+
+	// anything the agent wouldn't write back as it was, like a C3 agent's web label, is kept as it was
+	let values = agent_values(&agent);
+	let is_known = |key: &str| is_dependency_key(key) || is_script_key(key) || tag.is_written(key, &values);
+	agent.extra_int_values = tag.extra_int_values(is_known);
+	agent.extra_str_values = tag.extra_str_values(is_known);
+
+	agent_blocks.push(Block::Agent(agent));
 
 	Ok(agent_blocks)
 }
 
-pub fn write_agent_block(agent_block: &Agent, dependencies: &Vec<File>) -> Result<Bytes, Box<dyn Error>> {
+pub fn write_agent_block(agent_block: &Agent, dependencies: &Vec<File>, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let tag = agent_tag(agent_block, dependencies);
+	let content_buffer = write_tag_block(&tag.int_values, &tag.str_values);
+
+	let is_compressed = options.is_compressed(agent_block.compression, false, content_buffer.len());
+	write_block(if agent_block.game_support == GameSupport::Creatures3 { "AGNT" } else { "DSAG" }, &agent_block.name, &content_buffer, is_compressed, options.level)
+}
+
+// the values an agent is saved with, including the dependencies and scripts it uses
+pub fn agent_tag(agent_block: &Agent, dependencies: &Vec<File>) -> Tag {
 	let mut tag_scripts: Vec<&File> = Vec::new();
 	let mut tag_dependencies: Vec<&File> = Vec::new();
 	for dependency in dependencies {
@@ -100,16 +132,34 @@ pub fn write_agent_block(agent_block: &Agent, dependencies: &Vec<File>) -> Resul
 		}
 	}
 
+	let Tag { mut int_values, mut str_values } = agent_values(agent_block);
+
+	write_dependencies(&mut int_values, &mut str_values, &tag_dependencies);
+
+	int_values.push(("Script Count".to_string(), tag_scripts.len() as u32));
+	for (i, script) in tag_scripts.iter().enumerate() {
+		str_values.push((format!("Script {}", i + 1), cp1252::decode(&script.data)));
+	}
+
+	write_extra_values(&mut int_values, &mut str_values, &agent_block.extra_int_values, &agent_block.extra_str_values, is_agent_key);
+	restore_key_order(&mut int_values, &mut str_values, &agent_block.key_order);
+
+	Tag { int_values, str_values }
+}
+
+// the values an agent writes from its own fields, leaving out its dependencies and scripts
+fn agent_values(agent_block: &Agent) -> Tag {
 	let mut int_values: Vec<(String, u32)> = Vec::new();
 	let mut str_values: Vec<(String, String)> = Vec::new();
 
 	int_values.push(("Agent Type".to_string(), 0));
 
-	if file_stem(&agent_block.animation_file) != "" {
+	if !file_stem(&agent_block.animation_file).is_empty() {
 		str_values.push(("Agent Animation Gallery".to_string(), file_stem(&agent_block.animation_file)));
 		str_values.push(("Agent Animation File".to_string(), agent_block.animation_file.clone()));
 		str_values.push(("Agent Animation String".to_string(), agent_block.animation_string.clone()));
-		if agent_block.game_support != GameSupport::Creatures3 {
+		// C3 agents don't need it, but keep it if one was given so opening and saving doesn't lose it
+		if agent_block.game_support != GameSupport::Creatures3 || agent_block.sprite_first_image != 0 {
 			int_values.push(("Agent Sprite First Image".to_string(), agent_block.sprite_first_image));
 		}
 	}
@@ -132,42 +182,23 @@ pub fn write_agent_block(agent_block: &Agent, dependencies: &Vec<File>) -> Resul
 		}
 	}
 
-	write_dependencies(&mut int_values, &mut str_values, &tag_dependencies);
-
 	str_values.push(("Remove script".to_string(), agent_block.remove_script.clone()));
 
-	int_values.push(("Script Count".to_string(), tag_scripts.len() as u32));
-	for (i, script) in tag_scripts.iter().enumerate() {
-		str_values.push((format!("Script {}", i + 1), str::from_utf8(&script.data)?.to_string()));
-	}
-
-	let content_buffer = write_tag_block(&int_values, &str_values);
-
-	let block_header = BlockHeader {
-		id: (if agent_block.game_support == GameSupport::Creatures3{ "AGNT" } else { "DSAG" }).to_string(),
-		name: agent_block.name.clone(),
-		size_compressed: content_buffer.len(),
-		size_uncompressed: content_buffer.len(),
-		is_compressed: false
-	};
-
-	let mut buffer = BytesMut::new();
-	buffer.extend_from_slice(&write_block_header(block_header));
-	buffer.extend_from_slice(&content_buffer);
-	Ok(buffer.freeze())
+	Tag { int_values, str_values }
 }
 
 pub fn get_scripts(tag: &Tag, block_name: &str) -> Vec<Block> {
 	let mut scripts: Vec<Block> = Vec::new();
-	let num_scripts = *tag.int_values.get("Script Count").unwrap_or(&0) as usize;
+	let num_scripts = (tag.get_int("Script Count").unwrap_or(0) as usize).min(tag.str_values.len());
 	for i in 1..(num_scripts + 1) {
-		let script_text = tag.str_values.get(&format!("Script {}", i));
+		let script_text = tag.get_str(&format!("Script {}", i));
 		if let Some(script_text) = script_text {
 			scripts.push(Block::File(File {
 				name: if i == 1 { block_name.to_string() } else { format!("{} {}", block_name, i) },
 				extension: "cos".to_string(),
-				data: Bytes::from(script_text.clone()).to_vec(),
-				is_checked: false
+				data: cp1252::encode(script_text),
+				is_checked: false,
+				compression: Compression::Auto
 			}));
 		}
 	}
@@ -176,23 +207,41 @@ pub fn get_scripts(tag: &Tag, block_name: &str) -> Vec<Block> {
 
 pub fn get_descriptions(tag: &Tag) -> Vec<Description> {
 	let mut descriptions: Vec<Description> = Vec::new();
-	if let Some(description) = tag.str_values.get("Agent Description") {
+	if let Some(description) = tag.get_str("Agent Description") {
 		descriptions.push(Description { language: Language::English, text: description.clone() });
 	}
-	if let Some(description) = tag.str_values.get("Agent Description-de") {
+	if let Some(description) = tag.get_str("Agent Description-de") {
 		descriptions.push(Description { language: Language::German, text: description.clone() });
 	}
-	if let Some(description) = tag.str_values.get("Agent Description-es") {
+	if let Some(description) = tag.get_str("Agent Description-es") {
 		descriptions.push(Description { language: Language::Spanish, text: description.clone() });
 	}
-	if let Some(description) = tag.str_values.get("Agent Description-fr") {
+	if let Some(description) = tag.get_str("Agent Description-fr") {
 		descriptions.push(Description { language: Language::French, text: description.clone() });
 	}
-	if let Some(description) = tag.str_values.get("Agent Description-it") {
+	if let Some(description) = tag.get_str("Agent Description-it") {
 		descriptions.push(Description { language: Language::Italian, text: description.clone() });
 	}
-	if let Some(description) = tag.str_values.get("Agent Description-nl") {
+	if let Some(description) = tag.get_str("Agent Description-nl") {
 		descriptions.push(Description { language: Language::Dutch, text: description.clone() });
 	}
 	descriptions
 }
+
+// the keys an agent has a field for
+fn is_agent_key(key: &str) -> bool {
+	matches!(key,
+		"Agent Bioenergy Value" |
+		"Web Label" |
+		"Web URL" |
+		"Agent Animation File" |
+		"Agent Animation String" |
+		"Agent Sprite First Image" |
+		"Remove script" |
+		"Agent Description" |
+		"Agent Description-de" |
+		"Agent Description-es" |
+		"Agent Description-fr" |
+		"Agent Description-it" |
+		"Agent Description-nl")
+}
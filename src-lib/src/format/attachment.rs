@@ -0,0 +1,43 @@
+use crate::error::Error;
+
+// one line per sprite frame, each a list of x y pairs where other body parts join on
+pub type AttachmentPoints = Vec<Vec<(i32, i32)>>;
+
+pub fn parse(text: &str) -> Result<AttachmentPoints, Error> {
+	let mut lines: AttachmentPoints = Vec::new();
+	for (i, line) in text.lines().enumerate() {
+		if line.trim().is_empty() {
+			continue;
+		}
+		let mut values: Vec<i32> = Vec::new();
+		for value in line.split_whitespace() {
+			match value.parse::<i32>() {
+				Ok(value) => values.push(value),
+				Err(_) => return Err(Error::Syntax { line: i + 1, message: format!("\"{}\" is not a number", value) })
+			}
+		}
+		if values.len() % 2 != 0 {
+			return Err(Error::Syntax { line: i + 1, message: "Points need both an x and a y".to_string() });
+		}
+		lines.push(values.chunks(2).map(|pair| (pair[0], pair[1])).collect());
+	}
+	Ok(lines)
+}
+
+// checks the points against the width and height of each frame of the body part sprite
+pub fn check_frames(points: &AttachmentPoints, sprite_name: &str, frame_sizes: &[(u32, u32)]) -> Vec<String> {
+	let mut errors: Vec<String> = Vec::new();
+	if points.len() < frame_sizes.len() {
+		errors.push(format!("\"{}\" has {} images but only {} lines of points", sprite_name, frame_sizes.len(), points.len()));
+	} else if points.len() > frame_sizes.len() {
+		errors.push(format!("\"{}\" has {} images but there are {} lines of points", sprite_name, frame_sizes.len(), points.len()));
+	}
+	for (i, (line, (width, height))) in points.iter().zip(frame_sizes).enumerate() {
+		for (x, y) in line {
+			if *x < 0 || *y < 0 || *x >= *width as i32 || *y >= *height as i32 {
+				errors.push(format!("Point {} {} is outside image {} of \"{}\", which is {}x{}", x, y, i, sprite_name, width, height));
+			}
+		}
+	}
+	errors
+}
@@ -0,0 +1,426 @@
+use crate::error::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+	Word(String), // lowercased, so commands can be matched directly
+	Int(i32),
+	Float(f32),
+	Str(String),
+	ByteString(String)
+}
+
+#[derive(Clone, Debug)]
+pub struct Token {
+	pub kind: TokenKind,
+	pub text: String, // as written in the script
+	pub line: usize,
+	start: usize,
+	end: usize
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub struct Classifier {
+	pub family: u8,
+	pub genus: u8,
+	pub species: u16
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub enum ScriptKind {
+	Install,
+	Event { classifier: Classifier, event: u16 },
+	Remove
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Script {
+	pub kind: ScriptKind,
+	pub start_line: usize,
+	pub end_line: usize,
+	pub text: String,
+	#[serde(skip)]
+	pub tokens: Vec<Token>
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct CaosFile {
+	pub install_script: Option<Script>,
+	pub event_scripts: Vec<Script>,
+	pub remove_script: Option<Script>
+}
+
+impl Token {
+	pub fn is_word(&self, word: &str) -> bool {
+		matches!(&self.kind, TokenKind::Word(w) if w == word)
+	}
+
+	pub fn as_int(&self) -> Option<i32> {
+		match self.kind {
+			TokenKind::Int(value) => Some(value),
+			_ => None
+		}
+	}
+
+	pub fn as_str(&self) -> Option<&str> {
+		match &self.kind {
+			TokenKind::Str(value) => Some(value),
+			_ => None
+		}
+	}
+}
+
+impl Script {
+	// the script on one line with comments removed, the way it's stored in a remove script tag
+	pub fn compact(&self) -> String {
+		self.tokens.iter()
+			.filter(|token| !token.is_word("iscr") && !token.is_word("rscr"))
+			.map(|token| token.text.as_str())
+			.collect::<Vec<&str>>()
+			.join(" ")
+	}
+}
+
+impl CaosFile {
+	pub fn scripts(&self) -> impl Iterator<Item = &Script> {
+		self.install_script.iter().chain(self.event_scripts.iter()).chain(self.remove_script.iter())
+	}
+
+	pub fn classifiers(&self) -> Vec<Classifier> {
+		let mut classifiers: Vec<Classifier> = self.event_scripts.iter()
+			.filter_map(|script| match script.kind {
+				ScriptKind::Event { classifier, .. } => Some(classifier),
+				_ => None
+			})
+			.collect();
+		classifiers.sort();
+		classifiers.dedup();
+		classifiers
+	}
+
+	pub fn events(&self, classifier: &Classifier) -> Vec<u16> {
+		self.event_scripts.iter()
+			.filter_map(|script| match script.kind {
+				ScriptKind::Event { classifier: c, event } if c == *classifier => Some(event),
+				_ => None
+			})
+			.collect()
+	}
+}
+
+pub fn event_name(event: u16) -> Option<&'static str> {
+	match event {
+		0 => Some("Deactivate"),
+		1 => Some("Activate 1"),
+		2 => Some("Activate 2"),
+		3 => Some("Hit"),
+		4 => Some("Pickup"),
+		5 => Some("Drop"),
+		6 => Some("Collision"),
+		7 => Some("Bump"),
+		8 => Some("Impact"),
+		9 => Some("Timer"),
+		10 => Some("Constructor"),
+		11 => Some("Destructor"),
+		12 => Some("Eat"),
+		13 => Some("Hand Start Hold"),
+		14 => Some("Hand Stop Hold"),
+		92 => Some("UI Mouse Down"),
+		99 => Some("Mouse Down"),
+		100 => Some("Mouse Up"),
+		101 => Some("Mouse Enter"),
+		102 => Some("Mouse Leave"),
+		116 => Some("Got New Mail"),
+		117 => Some("Raw Mouse Wheel"),
+		118 => Some("Raw Mouse Clicked"),
+		119 => Some("Raw Mouse Moved"),
+		120 => Some("Raw Key Down"),
+		121 => Some("Raw Key Up"),
+		122 => Some("Selected Creature Changed"),
+		123 => Some("Translated Char"),
+		128 => Some("Pointer Activate 1"),
+		130 => Some("Pointer Deactivate"),
+		135 => Some("Keyboard Focus Lost"),
+		_ => None
+	}
+}
+
+pub fn tokenize(text: &str) -> Result<Vec<Token>, Error> {
+	let mut tokens: Vec<Token> = Vec::new();
+	let mut chars = text.char_indices().peekable();
+	let mut line = 1;
+
+	while let Some((start, c)) = chars.next() {
+		match c {
+			'\n' => { line += 1; }
+
+			c if c.is_whitespace() || c == ',' => {}
+
+			// comments run to the end of the line
+			'*' => {
+				while let Some((_, c)) = chars.peek() {
+					if *c == '\n' { break; }
+					chars.next();
+				}
+			}
+
+			'"' => {
+				let start_line = line;
+				let mut value = String::new();
+				let mut end = None;
+				while let Some((i, c)) = chars.next() {
+					match c {
+						'"' => { end = Some(i + 1); break; }
+						'\\' => match chars.next() {
+							Some((_, 'n')) => value.push('\n'),
+							Some((_, c)) => value.push(c),
+							None => break
+						},
+						'\n' => { line += 1; value.push(c); }
+						_ => value.push(c)
+					}
+				}
+				match end {
+					Some(end) => tokens.push(Token { kind: TokenKind::Str(value), text: text[start..end].to_string(), line: start_line, start, end }),
+					None => return Err(Error::Syntax { line: start_line, message: "String is missing its closing quote".to_string() })
+				}
+			}
+
+			'[' => {
+				let start_line = line;
+				let mut value = String::new();
+				let mut end = None;
+				for (i, c) in chars.by_ref() {
+					match c {
+						']' => { end = Some(i + 1); break; }
+						'\n' => { line += 1; value.push(c); }
+						_ => value.push(c)
+					}
+				}
+				match end {
+					Some(end) => tokens.push(Token { kind: TokenKind::ByteString(value), text: text[start..end].to_string(), line: start_line, start, end }),
+					None => return Err(Error::Syntax { line: start_line, message: "Byte string is missing its closing bracket".to_string() })
+				}
+			}
+
+			_ => {
+				let mut end = start + c.len_utf8();
+				while let Some((i, c)) = chars.peek() {
+					if c.is_whitespace() || *c == ',' { break; }
+					end = *i + c.len_utf8();
+					chars.next();
+				}
+				let word = &text[start..end];
+				let kind = if let Ok(value) = word.parse::<i32>() {
+					TokenKind::Int(value)
+				} else if let Some(value) = word.strip_prefix('%').and_then(|bits| i32::from_str_radix(bits, 2).ok()) {
+					TokenKind::Int(value)
+				} else if word.len() == 3 && word.starts_with('\'') && word.ends_with('\'') {
+					TokenKind::Int(word.chars().nth(1).unwrap_or_default() as i32)
+				} else if let Ok(value) = word.parse::<f32>() {
+					TokenKind::Float(value)
+				} else {
+					TokenKind::Word(word.to_ascii_lowercase())
+				};
+				tokens.push(Token { kind, text: word.to_string(), line, start, end });
+			}
+		}
+	}
+
+	Ok(tokens)
+}
+
+// keeps the original text of a script, including comments, even when it's split up by event scripts
+struct ScriptBuilder {
+	tokens: Vec<Token>,
+	spans: Vec<(usize, usize)>
+}
+
+impl ScriptBuilder {
+	fn new() -> Self {
+		ScriptBuilder { tokens: Vec::new(), spans: Vec::new() }
+	}
+
+	fn push(&mut self, token: Token, is_continuous: bool) {
+		match self.spans.last_mut() {
+			Some(span) if is_continuous => { span.1 = token.end; }
+			_ => { self.spans.push((token.start, token.end)); }
+		}
+		self.tokens.push(token);
+	}
+
+	fn build(self, text: &str, kind: ScriptKind) -> Option<Script> {
+		let start_line = self.tokens.first()?.line;
+		let end_line = self.tokens.last()?.line;
+		let text = self.spans.iter()
+			.map(|(start, end)| &text[*start..*end])
+			.collect::<Vec<&str>>()
+			.join("\n");
+		Some(Script { kind, start_line, end_line, text, tokens: self.tokens })
+	}
+}
+
+fn read_classifier_value(token: Option<&Token>, line: usize, max: i32) -> Result<i32, Error> {
+	match token.and_then(|t| t.as_int()) {
+		Some(value) if (0..=max).contains(&value) => Ok(value),
+		_ => Err(Error::Syntax { line, message: "scrp must be followed by family, genus, species and event numbers".to_string() })
+	}
+}
+
+pub fn parse(text: &str) -> Result<CaosFile, Error> {
+	let mut install_script = ScriptBuilder::new();
+	let mut remove_script = ScriptBuilder::new();
+	let mut event_scripts: Vec<Script> = Vec::new();
+	let mut is_remove = false;
+	let mut last_section: Option<bool> = None; // whether the previous token went to the remove script
+
+	let mut tokens = tokenize(text)?.into_iter();
+	while let Some(token) = tokens.next() {
+		if token.is_word("scrp") {
+			let line = token.line;
+			let family = tokens.next();
+			let genus = tokens.next();
+			let species = tokens.next();
+			let event_token = tokens.next();
+			let classifier = Classifier {
+				family: read_classifier_value(family.as_ref(), line, u8::MAX.into())? as u8,
+				genus: read_classifier_value(genus.as_ref(), line, u8::MAX.into())? as u8,
+				species: read_classifier_value(species.as_ref(), line, u16::MAX.into())? as u16
+			};
+			let event = read_classifier_value(event_token.as_ref(), line, u16::MAX.into())? as u16;
+
+			let mut script = ScriptBuilder::new();
+			script.push(token, true);
+			for header_token in [family, genus, species, event_token].into_iter().flatten() {
+				script.push(header_token, true);
+			}
+			let mut is_closed = false;
+			for body_token in tokens.by_ref() {
+				if body_token.is_word("scrp") || body_token.is_word("rscr") || body_token.is_word("iscr") {
+					return Err(Error::Syntax { line: body_token.line, message: format!("Script starting on line {} is missing endm", line) });
+				}
+				let is_endm = body_token.is_word("endm");
+				script.push(body_token, true);
+				if is_endm {
+					is_closed = true;
+					break;
+				}
+			}
+			if !is_closed {
+				return Err(Error::Syntax { line, message: "Script is missing endm".to_string() });
+			}
+			if let Some(script) = script.build(text, ScriptKind::Event { classifier, event }) {
+				event_scripts.push(script);
+			}
+			last_section = None;
+
+		} else if token.is_word("endm") {
+			return Err(Error::Syntax { line: token.line, message: "endm without a matching scrp".to_string() });
+
+		} else {
+			if token.is_word("rscr") {
+				is_remove = true;
+			} else if token.is_word("iscr") {
+				is_remove = false;
+			}
+			let is_continuous = last_section == Some(is_remove);
+			if is_remove {
+				remove_script.push(token, is_continuous);
+			} else {
+				install_script.push(token, is_continuous);
+			}
+			last_section = Some(is_remove);
+		}
+	}
+
+	Ok(CaosFile {
+		install_script: install_script.build(text, ScriptKind::Install),
+		event_scripts,
+		remove_script: remove_script.build(text, ScriptKind::Remove)
+	})
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub enum ReferenceKind {
+	Sprite,
+	Sound,
+	MusicTrack,
+	CatalogueTag,
+	Genome
+}
+
+// a file (or catalogue tag) that a script refers to by name
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Reference {
+	pub kind: ReferenceKind,
+	pub name: String,
+	pub line: usize
+}
+
+impl CaosFile {
+	pub fn references(&self) -> Vec<Reference> {
+		let mut references: Vec<Reference> = Vec::new();
+		for script in self.scripts() {
+			references.extend(find_references(&script.tokens));
+		}
+		references
+	}
+}
+
+// only names written as string literals can be found, since variables can't be followed without running the script
+fn find_references(tokens: &[Token]) -> Vec<Reference> {
+	let mut references: Vec<Reference> = Vec::new();
+	let mut add_reference = |kind: ReferenceKind, token: Option<&Token>| {
+		if let Some(token) = token {
+			if let Some(name) = token.as_str() {
+				if !name.is_empty() && !references.iter().any(|r: &Reference| r.kind == kind && r.name.eq_ignore_ascii_case(name)) {
+					references.push(Reference { kind, name: name.to_string(), line: token.line });
+				}
+			}
+		}
+	};
+
+	for (i, token) in tokens.iter().enumerate() {
+		let next = |n: usize| tokens.get(i + n);
+		if let TokenKind::Word(word) = &token.kind {
+			match word.as_str() {
+				// gall "sprite" first_image
+				"gall" => add_reference(ReferenceKind::Sprite, next(1)),
+
+				// new: simp family genus species "sprite" image_count first_image plane
+				"new:" if next(1).is_some_and(|t| t.is_word("simp") || t.is_word("comp") || t.is_word("vhcl")) => {
+					add_reference(ReferenceKind::Sprite, next(5));
+				}
+
+				// pat: dull part "sprite" first_image ...
+				"pat:" if next(1).is_some_and(|t| ["butt", "dull", "fixd", "text", "grph", "cmra"].iter().any(|w| t.is_word(w))) => {
+					add_reference(ReferenceKind::Sprite, next(3));
+					// pat: fixd part "sprite" first_image x y plane "font"
+					// pat: text part "sprite" first_image x y plane message_id "font"
+					if next(1).is_some_and(|t| t.is_word("fixd")) {
+						add_reference(ReferenceKind::Sprite, next(8));
+					} else if next(1).is_some_and(|t| t.is_word("text")) {
+						add_reference(ReferenceKind::Sprite, next(9));
+					}
+				}
+
+				"sndc" | "snde" | "sndl" | "sndq" => add_reference(ReferenceKind::Sound, next(1)),
+
+				// mmsc x y "track"
+				"mmsc" => add_reference(ReferenceKind::MusicTrack, next(3)),
+
+				"read" | "rean" | "reaq" => add_reference(ReferenceKind::CatalogueTag, next(1)),
+
+				// gene load agent slot "genome"
+				"gene" if next(1).is_some_and(|t| t.is_word("load")) => {
+					if let Some(position) = tokens[i..].iter().take(8).position(|t| t.as_str().is_some()) {
+						add_reference(ReferenceKind::Genome, tokens.get(i + position));
+					}
+				}
+
+				_ => {}
+			}
+		}
+	}
+
+	references
+}
@@ -0,0 +1,196 @@
+use crate::error::Error;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CatalogueEntry {
+	pub name: String,
+	pub is_array: bool,
+	pub is_override: bool,
+	pub declared_count: Option<usize>, // what the ARRAY line says, which may not match the strings
+	pub strings: Vec<String>,
+	pub comments: Vec<String>, // comment lines just above the entry
+	pub line: usize
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Catalogue {
+	pub entries: Vec<CatalogueEntry>,
+	pub trailing_comments: Vec<String>
+}
+
+enum Token {
+	Word(String),
+	Int(usize),
+	Str(String)
+}
+
+impl Catalogue {
+	pub fn has_tag(&self, name: &str) -> bool {
+		self.entries.iter().any(|entry| entry.name == name)
+	}
+
+	// problems that don't stop the file being read, but that the game will complain about
+	pub fn check(&self) -> Vec<String> {
+		let mut warnings: Vec<String> = Vec::new();
+		for (i, entry) in self.entries.iter().enumerate() {
+			if let Some(declared_count) = entry.declared_count {
+				if declared_count != entry.strings.len() {
+					warnings.push(format!("Line {}: ARRAY \"{}\" should have {} strings but has {}", entry.line, entry.name, declared_count, entry.strings.len()));
+				}
+			}
+			if entry.strings.is_empty() {
+				warnings.push(format!("Line {}: \"{}\" has no strings", entry.line, entry.name));
+			}
+			if !entry.is_override {
+				if let Some(original) = self.entries[..i].iter().find(|e| e.name == entry.name) {
+					warnings.push(format!("Line {}: \"{}\" is already defined on line {}", entry.line, entry.name, original.line));
+				}
+			}
+		}
+		warnings
+	}
+}
+
+pub fn parse(text: &str) -> Result<Catalogue, Error> {
+	let mut catalogue = Catalogue::default();
+	let mut comments: Vec<String> = Vec::new();
+
+	for (i, line) in text.lines().enumerate() {
+		let line_number = i + 1;
+		let trimmed = line.trim();
+		if trimmed.is_empty() {
+			continue;
+		}
+		if trimmed.starts_with('*') || trimmed.starts_with('#') {
+			comments.push(trimmed.to_string());
+			continue;
+		}
+
+		let tokens = tokenize(trimmed, line_number)?;
+		match tokens.first() {
+			Some(Token::Word(word)) if word.eq_ignore_ascii_case("tag") || word.eq_ignore_ascii_case("array") => {
+				let is_array = word.eq_ignore_ascii_case("array");
+				let mut rest = tokens[1..].iter().peekable();
+				let is_override = matches!(rest.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case("override"));
+				if is_override {
+					rest.next();
+				}
+				let name = match rest.next() {
+					Some(Token::Str(name)) => name.clone(),
+					_ => return Err(syntax_error(line_number, format!("Expected a quoted name after {}", word.to_ascii_uppercase())))
+				};
+				let declared_count = if is_array {
+					match rest.next() {
+						Some(Token::Int(count)) => Some(*count),
+						_ => return Err(syntax_error(line_number, format!("Expected a number of strings after ARRAY \"{}\"", name)))
+					}
+				} else {
+					None
+				};
+				if rest.next().is_some() {
+					return Err(syntax_error(line_number, format!("Unexpected text after \"{}\"", name)));
+				}
+				catalogue.entries.push(CatalogueEntry {
+					name,
+					is_array,
+					is_override,
+					declared_count,
+					strings: Vec::new(),
+					comments: std::mem::take(&mut comments),
+					line: line_number
+				});
+			}
+			_ => {
+				let entry = match catalogue.entries.last_mut() {
+					Some(entry) => entry,
+					None => return Err(syntax_error(line_number, "Text before the first TAG or ARRAY".to_string()))
+				};
+				for token in tokens {
+					match token {
+						Token::Str(string) => entry.strings.push(string),
+						_ => return Err(syntax_error(line_number, "Expected a quoted string".to_string()))
+					}
+				}
+			}
+		}
+	}
+
+	catalogue.trailing_comments = comments;
+	Ok(catalogue)
+}
+
+pub fn encode(catalogue: &Catalogue) -> String {
+	let mut text = String::new();
+	for entry in &catalogue.entries {
+		for comment in &entry.comments {
+			text.push_str(&format!("{}\n", comment));
+		}
+		let override_word = if entry.is_override { "OVERRIDE " } else { "" };
+		if entry.is_array {
+			text.push_str(&format!("ARRAY {}\"{}\" {}\n", override_word, escape(&entry.name), entry.strings.len()));
+		} else {
+			text.push_str(&format!("TAG {}\"{}\"\n", override_word, escape(&entry.name)));
+		}
+		for string in &entry.strings {
+			text.push_str(&format!("\"{}\"\n", escape(string)));
+		}
+		text.push('\n');
+	}
+	for comment in &catalogue.trailing_comments {
+		text.push_str(&format!("{}\n", comment));
+	}
+	text
+}
+
+fn tokenize(line: &str, line_number: usize) -> Result<Vec<Token>, Error> {
+	let mut tokens: Vec<Token> = Vec::new();
+	let mut chars = line.chars().peekable();
+	while let Some(c) = chars.next() {
+		if c.is_whitespace() {
+			continue;
+		}
+		if c == '"' {
+			let mut string = String::new();
+			let mut is_closed = false;
+			while let Some(c) = chars.next() {
+				match c {
+					'"' => {
+						is_closed = true;
+						break;
+					}
+					'\\' => match chars.next() {
+						Some('n') => string.push('\n'),
+						Some(c) => string.push(c),
+						None => {}
+					},
+					_ => string.push(c)
+				}
+			}
+			if !is_closed {
+				return Err(syntax_error(line_number, "String is missing its closing quote".to_string()));
+			}
+			tokens.push(Token::Str(string));
+		} else {
+			let mut word = c.to_string();
+			while let Some(c) = chars.peek() {
+				if c.is_whitespace() || *c == '"' {
+					break;
+				}
+				word.push(*c);
+				chars.next();
+			}
+			match word.parse::<usize>() {
+				Ok(value) => tokens.push(Token::Int(value)),
+				Err(_) => tokens.push(Token::Word(word))
+			}
+		}
+	}
+	Ok(tokens)
+}
+
+fn escape(string: &str) -> String {
+	string.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn syntax_error(line: usize, message: String) -> Error {
+	Error::Syntax { line, message }
+}
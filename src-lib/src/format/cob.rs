@@ -0,0 +1,544 @@
+use bytes::{ Bytes, BytesMut, Buf, BufMut };
+use image::RgbaImage;
+
+use crate::error::Error;
+use crate::sprite::{ c16, parse_pixel_565, EncodeOptions };
+use super::cp1252;
+use super::pray::{ Block, Compression, decompress };
+use super::file_block::File;
+use super::agent_block::{ Agent, GameSupport, Description, Language };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub enum CobVersion {
+	C1,
+	C2
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct CobDate {
+	pub day: u32,
+	pub month: u32,
+	pub year: u32
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Thumbnail {
+	pub width: u32,
+	pub height: u32,
+	pub pixels: Vec<u8> // 8-bit palette indexes for C1, 565 pixels for C2
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct CobAgent {
+	pub name: String,
+	pub description: String,
+	pub quantity_available: u16,
+	pub quantity_used: u32,
+	pub last_usage: u32,
+	pub reuse_interval: u32,
+	pub expiry: CobDate,
+	pub install_scripts: Vec<String>, // C2 only has one
+	pub event_scripts: Vec<String>,
+	pub remove_script: String,
+	pub dependencies: Vec<String>, // file names, C2 only
+	pub thumbnail: Option<Thumbnail>
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct CobAuthor {
+	pub created: CobDate,
+	pub version: u8,
+	pub revision: u8,
+	pub name: String,
+	pub email: String,
+	pub url: String,
+	pub comments: String
+}
+
+#[derive(Clone)]
+pub struct Cob {
+	pub version: CobVersion,
+	pub agents: Vec<CobAgent>,
+	pub author: Option<CobAuthor>,
+	pub files: Vec<File>
+}
+
+impl Thumbnail {
+	// C1 thumbnails need the game's palette, so only C2 ones can be turned into an image
+	pub fn to_image(&self) -> Option<RgbaImage> {
+		if self.pixels.len() != self.width as usize * self.height as usize * 2 {
+			return None;
+		}
+		let mut buffer = Bytes::copy_from_slice(&self.pixels);
+		let mut image = RgbaImage::new(self.width, self.height);
+		for y in 0..self.height {
+			for x in 0..self.width {
+				image.put_pixel(x, y, parse_pixel_565(buffer.get_u16_le()));
+			}
+		}
+		Some(image)
+	}
+}
+
+pub fn decode(contents: &[u8]) -> Result<Cob, Error> {
+	if contents.starts_with(b"cob2") {
+		return decode_c2(contents);
+	}
+	if contents.starts_with(&[1, 0]) {
+		return decode_c1(contents);
+	}
+	// C2 COBs are sometimes compressed as a whole
+	match decompress(contents) {
+		Ok(decoded_data) if decoded_data.starts_with(b"cob2") => decode_c2(&decoded_data),
+		_ => Err(Error::InvalidCob("File is not a Creatures 1 or Creatures 2 COB".to_string()))
+	}
+}
+
+pub fn encode(cob: &Cob) -> Result<Bytes, Error> {
+	match cob.version {
+		CobVersion::C1 => encode_c1(cob),
+		CobVersion::C2 => Ok(encode_c2(cob))
+	}
+}
+
+fn decode_c1(contents: &[u8]) -> Result<Cob, Error> {
+	let mut buffer = Bytes::copy_from_slice(contents);
+	if buffer.remaining() < 26 { return Err(Error::UnexpectedEnd("COB header")); }
+	buffer.advance(2); // version
+	let quantity_available = buffer.get_u16_le();
+	let expiry_month = buffer.get_u32_le();
+	let expiry_day = buffer.get_u32_le();
+	let expiry_year = buffer.get_u32_le();
+	let event_script_count = buffer.get_u16_le();
+	let install_script_count = buffer.get_u16_le();
+	let quantity_used = buffer.get_u32_le();
+
+	let mut event_scripts: Vec<String> = Vec::new();
+	for _ in 0..event_script_count {
+		event_scripts.push(read_pascal_string(&mut buffer)?);
+	}
+	let mut install_scripts: Vec<String> = Vec::new();
+	for _ in 0..install_script_count {
+		install_scripts.push(read_pascal_string(&mut buffer)?);
+	}
+
+	if buffer.remaining() < 10 { return Err(Error::UnexpectedEnd("COB picture")); }
+	let width = buffer.get_u32_le();
+	let height = buffer.get_u32_le();
+	buffer.advance(2); // width again
+	let size = width as usize * height as usize;
+	if buffer.remaining() < size { return Err(Error::UnexpectedEnd("COB picture")); }
+	let thumbnail = if size > 0 {
+		Some(Thumbnail { width, height, pixels: buffer.copy_to_bytes(size).to_vec() })
+	} else {
+		None
+	};
+
+	let name = read_pascal_string(&mut buffer)?;
+
+	Ok(Cob {
+		version: CobVersion::C1,
+		agents: vec![CobAgent {
+			name,
+			quantity_available,
+			quantity_used,
+			expiry: CobDate { day: expiry_day, month: expiry_month, year: expiry_year },
+			install_scripts,
+			event_scripts,
+			thumbnail,
+			..Default::default()
+		}],
+		author: None,
+		files: Vec::new()
+	})
+}
+
+fn encode_c1(cob: &Cob) -> Result<Bytes, Error> {
+	let agent = match cob.agents.first() {
+		Some(agent) => agent,
+		None => return Err(Error::InvalidCob("COB has no agent to save".to_string()))
+	};
+	let mut buffer = BytesMut::new();
+	buffer.put_u16_le(1);
+	buffer.put_u16_le(agent.quantity_available);
+	buffer.put_u32_le(agent.expiry.month);
+	buffer.put_u32_le(agent.expiry.day);
+	buffer.put_u32_le(agent.expiry.year);
+	buffer.put_u16_le(agent.event_scripts.len() as u16);
+	buffer.put_u16_le(agent.install_scripts.len() as u16);
+	buffer.put_u32_le(agent.quantity_used);
+	for script in agent.event_scripts.iter().chain(agent.install_scripts.iter()) {
+		write_pascal_string(&mut buffer, script);
+	}
+	match &agent.thumbnail {
+		Some(thumbnail) => {
+			buffer.put_u32_le(thumbnail.width);
+			buffer.put_u32_le(thumbnail.height);
+			buffer.put_u16_le(thumbnail.width as u16);
+			buffer.put_slice(&thumbnail.pixels);
+		}
+		None => {
+			buffer.put_u32_le(0);
+			buffer.put_u32_le(0);
+			buffer.put_u16_le(0);
+		}
+	}
+	write_pascal_string(&mut buffer, &agent.name);
+	Ok(buffer.freeze())
+}
+
+fn decode_c2(contents: &[u8]) -> Result<Cob, Error> {
+	let mut buffer = Bytes::copy_from_slice(contents);
+	buffer.advance(4);
+	let mut cob = Cob { version: CobVersion::C2, agents: Vec::new(), author: None, files: Vec::new() };
+
+	while buffer.remaining() >= 8 {
+		let id = buffer.copy_to_bytes(4);
+		let size = buffer.get_u32_le() as usize;
+		if buffer.remaining() < size { return Err(Error::UnexpectedEnd("COB block")); }
+		let mut block = buffer.copy_to_bytes(size);
+		match &id[..] {
+			b"agnt" => cob.agents.push(read_agent_block(&mut block)?),
+			b"auth" => cob.author = Some(read_author_block(&mut block)?),
+			b"file" => cob.files.push(read_file_block(&mut block)?),
+			_ => {}
+		}
+	}
+
+	Ok(cob)
+}
+
+fn encode_c2(cob: &Cob) -> Bytes {
+	let mut buffer = BytesMut::new();
+	buffer.put_slice(b"cob2");
+	for agent in &cob.agents {
+		write_block(&mut buffer, b"agnt", &write_agent_block(agent));
+	}
+	if let Some(author) = &cob.author {
+		write_block(&mut buffer, b"auth", &write_author_block(author));
+	}
+	for file in &cob.files {
+		write_block(&mut buffer, b"file", &write_file_block(file));
+	}
+	buffer.freeze()
+}
+
+fn read_agent_block(buffer: &mut Bytes) -> Result<CobAgent, Error> {
+	if buffer.remaining() < 26 { return Err(Error::UnexpectedEnd("COB agent")); }
+	let quantity_available = buffer.get_u16_le();
+	let last_usage = buffer.get_u32_le();
+	let reuse_interval = buffer.get_u32_le();
+	let day = buffer.get_u8() as u32;
+	let month = buffer.get_u8() as u32;
+	let year = buffer.get_u16_le() as u32;
+	buffer.advance(12); // reserved
+	let name = read_c_string(buffer)?;
+	let description = read_c_string(buffer)?;
+	let install_script = read_c_string(buffer)?;
+	let remove_script = read_c_string(buffer)?;
+
+	if buffer.remaining() < 2 { return Err(Error::UnexpectedEnd("COB agent")); }
+	let event_script_count = buffer.get_u16_le();
+	let mut event_scripts: Vec<String> = Vec::new();
+	for _ in 0..event_script_count {
+		event_scripts.push(read_c_string(buffer)?);
+	}
+
+	if buffer.remaining() < 2 { return Err(Error::UnexpectedEnd("COB agent")); }
+	let dependency_count = buffer.get_u16_le();
+	let mut dependencies: Vec<String> = Vec::new();
+	for _ in 0..dependency_count {
+		if buffer.remaining() < 2 { return Err(Error::UnexpectedEnd("COB agent")); }
+		buffer.advance(2); // file type, which the extension tells us anyway
+		dependencies.push(read_c_string(buffer)?);
+	}
+
+	if buffer.remaining() < 4 { return Err(Error::UnexpectedEnd("COB thumbnail")); }
+	let width = buffer.get_u16_le() as u32;
+	let height = buffer.get_u16_le() as u32;
+	let size = width as usize * height as usize * 2;
+	if buffer.remaining() < size { return Err(Error::UnexpectedEnd("COB thumbnail")); }
+	let thumbnail = if size > 0 {
+		Some(Thumbnail { width, height, pixels: buffer.copy_to_bytes(size).to_vec() })
+	} else {
+		None
+	};
+
+	Ok(CobAgent {
+		name,
+		description,
+		quantity_available,
+		quantity_used: 0,
+		last_usage,
+		reuse_interval,
+		expiry: CobDate { day, month, year },
+		install_scripts: vec![install_script],
+		event_scripts,
+		remove_script,
+		dependencies,
+		thumbnail
+	})
+}
+
+fn write_agent_block(agent: &CobAgent) -> Bytes {
+	let mut buffer = BytesMut::new();
+	buffer.put_u16_le(agent.quantity_available);
+	buffer.put_u32_le(agent.last_usage);
+	buffer.put_u32_le(agent.reuse_interval);
+	buffer.put_u8(agent.expiry.day as u8);
+	buffer.put_u8(agent.expiry.month as u8);
+	buffer.put_u16_le(agent.expiry.year as u16);
+	buffer.put_bytes(0, 12);
+	write_c_string(&mut buffer, &agent.name);
+	write_c_string(&mut buffer, &agent.description);
+	write_c_string(&mut buffer, &agent.install_scripts.join("\n"));
+	write_c_string(&mut buffer, &agent.remove_script);
+	buffer.put_u16_le(agent.event_scripts.len() as u16);
+	for script in &agent.event_scripts {
+		write_c_string(&mut buffer, script);
+	}
+	buffer.put_u16_le(agent.dependencies.len() as u16);
+	for dependency in &agent.dependencies {
+		buffer.put_u16_le(file_type(dependency));
+		write_c_string(&mut buffer, dependency);
+	}
+	match &agent.thumbnail {
+		Some(thumbnail) => {
+			buffer.put_u16_le(thumbnail.width as u16);
+			buffer.put_u16_le(thumbnail.height as u16);
+			buffer.put_slice(&thumbnail.pixels);
+		}
+		None => {
+			buffer.put_u16_le(0);
+			buffer.put_u16_le(0);
+		}
+	}
+	buffer.freeze()
+}
+
+fn read_author_block(buffer: &mut Bytes) -> Result<CobAuthor, Error> {
+	if buffer.remaining() < 6 { return Err(Error::UnexpectedEnd("COB author")); }
+	let day = buffer.get_u8() as u32;
+	let month = buffer.get_u8() as u32;
+	let year = buffer.get_u16_le() as u32;
+	let version = buffer.get_u8();
+	let revision = buffer.get_u8();
+	Ok(CobAuthor {
+		created: CobDate { day, month, year },
+		version,
+		revision,
+		name: read_c_string(buffer)?,
+		email: read_c_string(buffer)?,
+		url: read_c_string(buffer)?,
+		comments: read_c_string(buffer)?
+	})
+}
+
+fn write_author_block(author: &CobAuthor) -> Bytes {
+	let mut buffer = BytesMut::new();
+	buffer.put_u8(author.created.day as u8);
+	buffer.put_u8(author.created.month as u8);
+	buffer.put_u16_le(author.created.year as u16);
+	buffer.put_u8(author.version);
+	buffer.put_u8(author.revision);
+	write_c_string(&mut buffer, &author.name);
+	write_c_string(&mut buffer, &author.email);
+	write_c_string(&mut buffer, &author.url);
+	write_c_string(&mut buffer, &author.comments);
+	buffer.freeze()
+}
+
+fn read_file_block(buffer: &mut Bytes) -> Result<File, Error> {
+	if buffer.remaining() < 10 { return Err(Error::UnexpectedEnd("COB file")); }
+	buffer.advance(6); // file type and reserved
+	let size = buffer.get_u32_le() as usize;
+	let filename = read_c_string(buffer)?;
+	if buffer.remaining() < size { return Err(Error::UnexpectedEnd("COB file")); }
+	let data = buffer.copy_to_bytes(size).to_vec();
+	let (name, extension) = match filename.rsplit_once('.') {
+		Some((name, extension)) => (name.to_string(), extension.to_ascii_lowercase()),
+		None => (filename, String::new())
+	};
+	Ok(File { name, extension, data, is_checked: false, compression: Compression::Auto })
+}
+
+fn write_file_block(file: &File) -> Bytes {
+	let mut buffer = BytesMut::new();
+	buffer.put_u16_le(file_type(&file.filename()));
+	buffer.put_u32_le(0);
+	buffer.put_u32_le(file.data.len() as u32);
+	write_c_string(&mut buffer, &file.filename());
+	buffer.put_slice(&file.data);
+	buffer.freeze()
+}
+
+fn write_block(buffer: &mut BytesMut, id: &[u8; 4], contents: &[u8]) {
+	buffer.put_slice(id);
+	buffer.put_u32_le(contents.len() as u32);
+	buffer.put_slice(contents);
+}
+
+// 0 = sprite, 1 = sound
+fn file_type(filename: &str) -> u16 {
+	if filename.to_ascii_lowercase().ends_with(".wav") { 1 } else { 0 }
+}
+
+fn read_c_string(buffer: &mut Bytes) -> Result<String, Error> {
+	match buffer.iter().position(|byte| *byte == 0) {
+		Some(length) => {
+			let string = cp1252::decode(&buffer.copy_to_bytes(length));
+			buffer.advance(1);
+			Ok(string)
+		}
+		None => Err(Error::UnexpectedEnd("COB string"))
+	}
+}
+
+fn write_c_string(buffer: &mut BytesMut, string: &str) {
+	buffer.put_slice(&cp1252::encode(string));
+	buffer.put_u8(0);
+}
+
+// a byte of length, or 255 followed by two bytes of length for longer strings
+fn read_pascal_string(buffer: &mut Bytes) -> Result<String, Error> {
+	if buffer.remaining() < 1 { return Err(Error::UnexpectedEnd("COB string")); }
+	let mut length = buffer.get_u8() as usize;
+	if length == 255 {
+		if buffer.remaining() < 2 { return Err(Error::UnexpectedEnd("COB string")); }
+		length = buffer.get_u16_le() as usize;
+	}
+	if buffer.remaining() < length { return Err(Error::UnexpectedEnd("COB string")); }
+	Ok(cp1252::decode(&buffer.copy_to_bytes(length)))
+}
+
+fn write_pascal_string(buffer: &mut BytesMut, string: &str) {
+	let bytes = cp1252::encode(string);
+	if bytes.len() < 255 {
+		buffer.put_u8(bytes.len() as u8);
+	} else {
+		buffer.put_u8(255);
+		buffer.put_u16_le(bytes.len() as u16);
+	}
+	buffer.put_slice(&bytes);
+}
+
+// builds Docking Station agent tags from the COB, with its scripts gathered into a .cos file for each agent
+// and its thumbnail turned into a sprite for the injector; the scripts are left as they are and will need porting
+pub fn convert_to_agent(cob: &Cob) -> Result<Vec<Block>, Error> {
+	let mut blocks: Vec<Block> = Vec::new();
+	let mut files: Vec<File> = cob.files.clone();
+
+	for agent in &cob.agents {
+		let stem = file_name_for(&agent.name);
+		let mut dependencies: Vec<String> = Vec::new();
+
+		let script = File {
+			name: stem.clone(),
+			extension: "cos".to_string(),
+			data: cp1252::encode(&script_file_text(agent)),
+			is_checked: false,
+			compression: Compression::Auto
+		};
+		dependencies.push(script.filename());
+		files.push(script);
+
+		let mut animation_file = String::new();
+		if let Some(image) = agent.thumbnail.as_ref().and_then(|thumbnail| thumbnail.to_image()) {
+			let sprite = File {
+				name: stem.clone(),
+				extension: "c16".to_string(),
+				data: c16::encode(&[image], &EncodeOptions::default())?.to_vec(),
+				is_checked: false,
+				compression: Compression::Auto
+			};
+			animation_file = sprite.filename();
+			dependencies.push(sprite.filename());
+			files.push(sprite);
+		}
+
+		// a COB with one agent ships all its files for it, otherwise go by what each agent lists
+		for file in &cob.files {
+			let is_listed = agent.dependencies.iter().any(|d| d.eq_ignore_ascii_case(&file.filename()));
+			if (cob.agents.len() == 1 || is_listed) && !dependencies.contains(&file.filename()) {
+				dependencies.push(file.filename());
+			}
+		}
+
+		let (web_label, web_url) = match &cob.author {
+			Some(author) => (author.name.clone(), author.url.clone()),
+			None => (String::new(), String::new())
+		};
+
+		blocks.push(Block::Agent(Agent {
+			name: agent.name.clone(),
+			game_support: GameSupport::DockingStation,
+			descriptions: vec![Description::new(Language::English, agent.description.clone())],
+			bioenergy: 0,
+			web_label,
+			web_url,
+			animation_string: if animation_file.is_empty() { String::new() } else { "0".to_string() },
+			animation_file,
+			sprite_first_image: 0,
+			remove_script: split_commands(&agent.remove_script).join(" "),
+			dependencies,
+			extra_int_values: Vec::new(),
+			extra_str_values: Vec::new(),
+			key_order: Vec::new(),
+			compression: Compression::Auto
+		}));
+	}
+
+	blocks.extend(files.into_iter().map(Block::File));
+	Ok(blocks)
+}
+
+fn script_file_text(agent: &CobAgent) -> String {
+	let mut sections: Vec<String> = vec![format!("* {}, converted from a COB; these scripts are unchanged and will need porting to C3/DS CAOS", agent.name)];
+	for script in &agent.install_scripts {
+		if !script.trim().is_empty() {
+			sections.push(split_commands(script).join("\n"));
+		}
+	}
+	for script in &agent.event_scripts {
+		let mut lines = split_commands(script);
+		if !lines.last().is_some_and(|line| line.eq_ignore_ascii_case("endm")) {
+			lines.push("endm".to_string());
+		}
+		sections.push(lines.join("\n"));
+	}
+	if !agent.remove_script.trim().is_empty() {
+		sections.push(format!("rscr\n{}", split_commands(&agent.remove_script).join("\n")));
+	}
+	sections.join("\n\n") + "\n"
+}
+
+// older CAOS separates commands with commas, except inside [text]
+fn split_commands(script: &str) -> Vec<String> {
+	let mut commands: Vec<String> = Vec::new();
+	let mut command = String::new();
+	let mut in_text = false;
+	for c in script.chars() {
+		match c {
+			'[' => { in_text = true; command.push(c); }
+			']' => { in_text = false; command.push(c); }
+			',' | '\n' | '\r' if !in_text => {
+				if !command.trim().is_empty() {
+					commands.push(command.trim().to_string());
+				}
+				command.clear();
+			}
+			_ => command.push(c)
+		}
+	}
+	if !command.trim().is_empty() {
+		commands.push(command.trim().to_string());
+	}
+	commands
+}
+
+fn file_name_for(agent_name: &str) -> String {
+	let name: String = agent_name.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+		.collect();
+	if name.is_empty() { "agent".to_string() } else { name }
+}
@@ -0,0 +1,42 @@
+// Windows-1252, the text encoding used by Creatures 3 and Docking Station
+
+// characters for bytes 0x80 to 0x9f; undefined bytes map to the matching C1 control character so they survive a round-trip
+static HIGH_CHARS: [char; 32] = [
+	'\u{20ac}', '\u{0081}', '\u{201a}', '\u{0192}', '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}',
+	'\u{02c6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008d}', '\u{017d}', '\u{008f}',
+	'\u{0090}', '\u{2018}', '\u{2019}', '\u{201c}', '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}',
+	'\u{02dc}', '\u{2122}', '\u{0161}', '\u{203a}', '\u{0153}', '\u{009d}', '\u{017e}', '\u{0178}'
+];
+
+pub fn decode_byte(byte: u8) -> char {
+	match byte {
+		0x80..=0x9f => HIGH_CHARS[(byte - 0x80) as usize],
+		_ => byte as char
+	}
+}
+
+pub fn encode_char(char: char) -> Option<u8> {
+	match char as u32 {
+		0x00..=0x7f | 0xa0..=0xff => Some(char as u8),
+		_ => HIGH_CHARS.iter().position(|c| *c == char).map(|i| 0x80 + i as u8)
+	}
+}
+
+pub fn decode(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| decode_byte(*byte)).collect()
+}
+
+// characters that can't be represented are replaced with "?"
+pub fn encode(string: &str) -> Vec<u8> {
+	string.chars().map(|char| encode_char(char).unwrap_or(b'?')).collect()
+}
+
+pub fn unencodable_chars(string: &str) -> Vec<char> {
+	let mut chars: Vec<char> = Vec::new();
+	for char in string.chars() {
+		if encode_char(char).is_none() && !chars.contains(&char) {
+			chars.push(char);
+		}
+	}
+	chars
+}
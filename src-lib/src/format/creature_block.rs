@@ -0,0 +1,327 @@
+use bytes::{ Bytes, BytesMut, BufMut };
+
+use crate::error::Error;
+use super::cp1252;
+use super::agent_block::GameSupport;
+use super::pray::{
+	Block,
+	Tag,
+	Compression,
+	CompressionOptions,
+	read_tag_block,
+	write_block,
+	write_tag_block,
+	write_extra_values,
+	restore_key_order,
+	read_u32,
+	read_string
+};
+
+// the tag describing an exported creature, named after its moniker
+#[derive(Clone, serde::Serialize)]
+pub struct ExportedCreature {
+	pub name: String,
+	pub game_support: GameSupport,
+	pub creature_name: String,
+	pub genome_moniker: String,
+	pub gender: u32, // 1: Male, 2: Female
+	pub genus: u32, // 1: Norn, 2: Grendel, 3: Ettin, 4: Geat
+	pub variant: u32,
+	pub life_stage: u32,
+	pub age_in_ticks: u32,
+	pub pregnancy_status: u32,
+	pub world_name: String,
+	pub world_uid: String,
+	pub extra_int_values: Vec<(String, u32)>,
+	pub extra_str_values: Vec<(String, String)>,
+	#[serde(skip)]
+	pub key_order: Vec<String>,
+	pub compression: Compression
+}
+
+// the life events the game keeps for a creature, as shown in the Creature History kit
+#[derive(Clone, serde::Serialize)]
+pub struct History {
+	pub name: String,
+	pub format: u32,
+	pub moniker: String,
+	pub second_moniker: String, // written twice, and kept as read in case the two differ
+	pub creature_name: String,
+	pub gender: u32,
+	pub genus: u32,
+	pub variant: u32,
+	pub events: Vec<LifeEvent>,
+	pub mutations: u32,
+	pub crossovers: u32,
+	pub has_network_fields: bool, // Docking Station adds a user ID to each event
+	pub trailing_data: Vec<u8>,
+	pub compression: Compression
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct LifeEvent {
+	pub event_type: u32, // see EVENT_TYPES
+	pub world_time: u32,
+	pub age_in_ticks: u32,
+	pub real_time: u32, // seconds since 1970
+	pub life_stage: u32,
+	pub moniker_1: String,
+	pub moniker_2: String,
+	pub user_text: String,
+	pub photo: String,
+	pub world_name: String,
+	pub world_uid: String,
+	pub network_user: String,
+	pub network_values: (u32, u32)
+}
+
+// the creature itself as saved by the game, which can't be changed outside of it
+#[derive(Clone, serde::Serialize)]
+pub struct CreatureData {
+	pub name: String,
+	pub data: Vec<u8>,
+	pub compression: Compression
+}
+
+// a snapshot taken in game, stored as an S16 sprite
+#[derive(Clone, serde::Serialize)]
+pub struct Photo {
+	pub name: String,
+	pub data: Vec<u8>,
+	pub compression: Compression
+}
+
+impl Photo {
+	pub fn filename(&self) -> String {
+		if self.name.to_ascii_lowercase().ends_with(".s16") {
+			self.name.clone()
+		} else {
+			format!("{}.s16", self.name)
+		}
+	}
+}
+
+pub const EVENT_TYPES: [&str; 18] = [
+	"Conceived", "Spliced", "Engineered", "Hatched", "Aged", "Exported", "Imported", "Died", "Became Pregnant",
+	"Made Pregnant", "Child Born", "Laid By Mother", "Laid An Egg", "Photographed", "Cloned", "Clone Source",
+	"Warped Out", "Warped In"
+];
+
+pub fn read_export_block(buffer: &mut Bytes, name: &str, game_support: GameSupport, compression: Compression) -> Result<Vec<Block>, Error> {
+	let tag = read_tag_block(buffer)?;
+
+	let mut export_block = ExportedCreature {
+		name: name.to_string(),
+		game_support,
+		creature_name: tag.get_str("Creature Name").cloned().unwrap_or_default(),
+		genome_moniker: tag.get_str("Genome Moniker").cloned().unwrap_or_default(),
+		gender: tag.get_int("Gender").unwrap_or(0),
+		genus: tag.get_int("Genus").unwrap_or(0),
+		variant: tag.get_int("Variant").unwrap_or(0),
+		life_stage: tag.get_int("Creature Life Stage").unwrap_or(0),
+		age_in_ticks: tag.get_int("Creature Age In Ticks").unwrap_or(0),
+		pregnancy_status: tag.get_int("Pregnancy Status").unwrap_or(0),
+		world_name: tag.get_str("Exported From World Name").cloned().unwrap_or_default(),
+		world_uid: tag.get_str("Exported From World UID").cloned().unwrap_or_default(),
+		extra_int_values: Vec::new(),
+		extra_str_values: Vec::new(),
+		key_order: tag.key_order(),
+		compression
+	};
+
+	let values = export_values(&export_block);
+	export_block.extra_int_values = tag.extra_int_values(|key| tag.is_written(key, &values));
+	export_block.extra_str_values = tag.extra_str_values(|key| tag.is_written(key, &values));
+
+	Ok(vec![Block::Export(export_block)])
+}
+
+pub fn write_export_block(export_block: &ExportedCreature, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let tag = export_tag(export_block);
+	let content_buffer = write_tag_block(&tag.int_values, &tag.str_values);
+	let id = match export_block.game_support {
+		GameSupport::Creatures3 => "EXPC",
+		GameSupport::DockingStation => "DSEX"
+	};
+	let is_compressed = options.is_compressed(export_block.compression, false, content_buffer.len());
+	write_block(id, &export_block.name, &content_buffer, is_compressed, options.level)
+}
+
+pub fn export_tag(export_block: &ExportedCreature) -> Tag {
+	let Tag { mut int_values, mut str_values } = export_values(export_block);
+
+	write_extra_values(&mut int_values, &mut str_values, &export_block.extra_int_values, &export_block.extra_str_values, is_export_key);
+	restore_key_order(&mut int_values, &mut str_values, &export_block.key_order);
+
+	Tag { int_values, str_values }
+}
+
+// the values an exported creature writes from its own fields
+fn export_values(export_block: &ExportedCreature) -> Tag {
+	let mut int_values: Vec<(String, u32)> = Vec::new();
+	let mut str_values: Vec<(String, String)> = Vec::new();
+
+	str_values.push(("Creature Name".to_string(), export_block.creature_name.clone()));
+	if !export_block.genome_moniker.is_empty() {
+		str_values.push(("Genome Moniker".to_string(), export_block.genome_moniker.clone()));
+	}
+	int_values.push(("Gender".to_string(), export_block.gender));
+	int_values.push(("Genus".to_string(), export_block.genus));
+	int_values.push(("Variant".to_string(), export_block.variant));
+	int_values.push(("Creature Life Stage".to_string(), export_block.life_stage));
+	int_values.push(("Creature Age In Ticks".to_string(), export_block.age_in_ticks));
+	int_values.push(("Pregnancy Status".to_string(), export_block.pregnancy_status));
+	str_values.push(("Exported From World Name".to_string(), export_block.world_name.clone()));
+	str_values.push(("Exported From World UID".to_string(), export_block.world_uid.clone()));
+
+	Tag { int_values, str_values }
+}
+
+// the keys an exported creature has a field for
+fn is_export_key(key: &str) -> bool {
+	matches!(key,
+		"Creature Name" |
+		"Genome Moniker" |
+		"Gender" |
+		"Genus" |
+		"Variant" |
+		"Creature Life Stage" |
+		"Creature Age In Ticks" |
+		"Pregnancy Status" |
+		"Exported From World Name" |
+		"Exported From World UID")
+}
+
+// Docking Station events carry extra network fields, so the layout follows the game the creature was exported from,
+// and when that isn't known, only a layout that reads the whole block is taken
+pub fn read_history_block(contents: &Bytes, name: &str, game_support: Option<GameSupport>, compression: Compression) -> Result<History, Error> {
+	match game_support {
+		Some(game_support) => read_history(&mut contents.clone(), name, game_support == GameSupport::DockingStation, compression),
+		None => {
+			let histories = [true, false].map(|has_network_fields| read_history(&mut contents.clone(), name, has_network_fields, compression));
+			let mut histories = histories.into_iter().flatten().filter(|history| history.trailing_data.is_empty());
+			histories.next().ok_or(Error::InvalidHistory("History doesn't match either game's layout".to_string()))
+		}
+	}
+}
+
+fn read_history(buffer: &mut Bytes, name: &str, has_network_fields: bool, compression: Compression) -> Result<History, Error> {
+	let format = read_u32(buffer)?;
+	let moniker = read_history_string(buffer)?;
+	let second_moniker = read_history_string(buffer)?;
+	let creature_name = read_history_string(buffer)?;
+	let gender = read_u32(buffer)?;
+	let genus = read_u32(buffer)?;
+	let variant = read_u32(buffer)?;
+
+	let event_count = read_u32(buffer)? as usize;
+	let mut events: Vec<LifeEvent> = Vec::new();
+	for _ in 0..event_count {
+		let event_type = read_u32(buffer)?;
+		let world_time = read_u32(buffer)?;
+		let age_in_ticks = read_u32(buffer)?;
+		let real_time = read_u32(buffer)?;
+		let life_stage = read_u32(buffer)?;
+		let moniker_1 = read_history_string(buffer)?;
+		let moniker_2 = read_history_string(buffer)?;
+		let user_text = read_history_string(buffer)?;
+		let photo = read_history_string(buffer)?;
+		let world_name = read_history_string(buffer)?;
+		let world_uid = read_history_string(buffer)?;
+		let (network_user, network_values) = if has_network_fields {
+			(read_history_string(buffer)?, (read_u32(buffer)?, read_u32(buffer)?))
+		} else {
+			(String::new(), (0, 0))
+		};
+		events.push(LifeEvent {
+			event_type, world_time, age_in_ticks, real_time, life_stage,
+			moniker_1, moniker_2, user_text, photo, world_name, world_uid,
+			network_user, network_values
+		});
+	}
+
+	let mutations = read_u32(buffer)?;
+	let crossovers = read_u32(buffer)?;
+
+	Ok(History {
+		name: name.to_string(),
+		format,
+		moniker,
+		second_moniker,
+		creature_name,
+		gender,
+		genus,
+		variant,
+		events,
+		mutations,
+		crossovers,
+		has_network_fields,
+		trailing_data: buffer.to_vec(),
+		compression
+	})
+}
+
+pub fn write_history_block(history: &History, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let buffer = write_history(history);
+	let is_compressed = options.is_compressed(history.compression, false, buffer.len());
+	write_block("GLST", &history.name, &buffer, is_compressed, options.level)
+}
+
+pub fn write_history(history: &History) -> Bytes {
+	let mut buffer = BytesMut::new();
+	buffer.put_u32_le(history.format);
+	write_history_string(&mut buffer, &history.moniker);
+	write_history_string(&mut buffer, &history.second_moniker);
+	write_history_string(&mut buffer, &history.creature_name);
+	buffer.put_u32_le(history.gender);
+	buffer.put_u32_le(history.genus);
+	buffer.put_u32_le(history.variant);
+
+	buffer.put_u32_le(history.events.len() as u32);
+	for event in &history.events {
+		buffer.put_u32_le(event.event_type);
+		buffer.put_u32_le(event.world_time);
+		buffer.put_u32_le(event.age_in_ticks);
+		buffer.put_u32_le(event.real_time);
+		buffer.put_u32_le(event.life_stage);
+		write_history_string(&mut buffer, &event.moniker_1);
+		write_history_string(&mut buffer, &event.moniker_2);
+		write_history_string(&mut buffer, &event.user_text);
+		write_history_string(&mut buffer, &event.photo);
+		write_history_string(&mut buffer, &event.world_name);
+		write_history_string(&mut buffer, &event.world_uid);
+		if history.has_network_fields {
+			write_history_string(&mut buffer, &event.network_user);
+			buffer.put_u32_le(event.network_values.0);
+			buffer.put_u32_le(event.network_values.1);
+		}
+	}
+
+	buffer.put_u32_le(history.mutations);
+	buffer.put_u32_le(history.crossovers);
+	buffer.extend_from_slice(&history.trailing_data);
+
+	buffer.freeze()
+}
+
+fn read_history_string(buffer: &mut Bytes) -> Result<String, Error> {
+	let length = read_u32(buffer)? as usize;
+	read_string(buffer, length)
+}
+
+fn write_history_string(buffer: &mut BytesMut, string: &str) {
+	let bytes = cp1252::encode(string);
+	buffer.put_u32_le(bytes.len() as u32);
+	buffer.extend_from_slice(&bytes);
+}
+
+// creature data and photos are stored like files, since they're big and not made of keys and values
+pub fn write_creature_data_block(creature_data: &CreatureData, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let is_compressed = options.is_compressed(creature_data.compression, true, creature_data.data.len());
+	write_block("CREA", &creature_data.name, &creature_data.data, is_compressed, options.level)
+}
+
+pub fn write_photo_block(photo: &Photo, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let is_compressed = options.is_compressed(photo.compression, true, photo.data.len());
+	write_block("PHOT", &photo.name, &photo.data, is_compressed, options.level)
+}
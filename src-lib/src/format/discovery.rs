@@ -0,0 +1,94 @@
+use super::caos::{ self, Reference, ReferenceKind };
+use super::catalogue;
+use super::mng;
+use super::cp1252;
+use super::file_block::File;
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct MissingReference {
+	pub script_file: String,
+	pub reference: Reference
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct DiscoveryReport {
+	pub found: Vec<String>, // dependency file names that the scripts use
+	pub missing: Vec<MissingReference>,
+	pub errors: Vec<String>
+}
+
+// scans scripts for the files they use, and sorts them into ones the agent has and ones it's missing
+pub fn discover_dependencies(scripts: &[&File], dependencies: &[File]) -> DiscoveryReport {
+	let mut report = DiscoveryReport::default();
+	for script in scripts {
+		let caos_file = match caos::parse(&cp1252::decode(&script.data)) {
+			Ok(caos_file) => caos_file,
+			Err(why) => {
+				report.errors.push(format!("{}: {}", script.filename(), why));
+				continue;
+			}
+		};
+		for reference in caos_file.references() {
+			let matches = find_referenced_files(&reference, dependencies);
+			if matches.is_empty() {
+				report.missing.push(MissingReference { script_file: script.filename(), reference });
+			}
+			for file in matches {
+				if !report.found.contains(&file.filename()) {
+					report.found.push(file.filename());
+				}
+			}
+		}
+	}
+	report
+}
+
+pub fn find_referenced_files<'a>(reference: &Reference, dependencies: &'a [File]) -> Vec<&'a File> {
+	dependencies.iter().filter(|file| {
+		let extension = file.extension.to_ascii_lowercase();
+		match reference.kind {
+			ReferenceKind::Sprite => {
+				(extension == "c16" || extension == "s16") && file.name.eq_ignore_ascii_case(&reference.name)
+			}
+			ReferenceKind::Sound => {
+				extension == "wav" && file.name.eq_ignore_ascii_case(&reference.name)
+			}
+			ReferenceKind::MusicTrack => {
+				extension == "mng" && music_has_track(file, &reference.name)
+			}
+			ReferenceKind::CatalogueTag => {
+				extension == "catalogue" && catalogue_has_tag(file, &reference.name)
+			}
+			ReferenceKind::Genome => {
+				extension == "gen" && matches_wildcard(&file.name.to_ascii_lowercase(), &reference.name.to_ascii_lowercase())
+			}
+		}
+	}).collect()
+}
+
+fn catalogue_has_tag(file: &File, tag_name: &str) -> bool {
+	match catalogue::parse(&cp1252::decode(&file.data)) {
+		Ok(catalogue) => catalogue.has_tag(tag_name),
+		Err(_) => false
+	}
+}
+
+fn music_has_track(file: &File, track_name: &str) -> bool {
+	match mng::decode(&file.data) {
+		Ok(music) => music.track_names().iter().any(|name| name.eq_ignore_ascii_case(track_name)),
+		Err(_) => false
+	}
+}
+
+// genome names in gene load can use * to match any number of characters
+fn matches_wildcard(name: &str, pattern: &str) -> bool {
+	match pattern.split_once('*') {
+		Some((prefix, rest)) => match name.strip_prefix(prefix) {
+			Some(name) => (0..=name.len())
+				.filter(|i| name.is_char_boundary(*i))
+				.any(|i| matches_wildcard(&name[i..], rest)),
+			None => false
+		},
+		None => name == pattern
+	}
+}
@@ -0,0 +1,129 @@
+use bytes::Bytes;
+
+use crate::error::Error;
+use super::file_block::File;
+use super::pray::{
+	Block,
+	Tag,
+	Compression,
+	CompressionOptions,
+	read_tag_block,
+	write_block,
+	write_tag_block,
+	get_dependencies,
+	write_dependencies,
+	write_extra_values,
+	restore_key_order,
+	is_dependency_key,
+	file_stem
+};
+
+#[derive(Clone, serde::Serialize)]
+pub struct Egg {
+	pub name: String,
+	pub genetics_file: String,
+	pub genetics_file_mother: String,
+	pub genetics_file_father: String,
+	pub sprite_file_male: String,
+	pub sprite_file_female: String,
+	pub animation_string: String,
+	pub dependencies: Vec<String>,
+	pub extra_int_values: Vec<(String, u32)>,
+	pub extra_str_values: Vec<(String, String)>,
+	#[serde(skip)]
+	pub key_order: Vec<String>,
+	pub compression: Compression
+}
+
+pub fn read_egg_block(buffer: &mut Bytes, name: &str, compression: Compression) -> Result<Vec<Block>, Error> {
+	let tag = read_tag_block(buffer)?;
+
+	let mut egg_block = Egg {
+		name: name.to_string(),
+		genetics_file: genetics_file_name(tag.get_str("Genetics File")),
+		genetics_file_mother: genetics_file_name(tag.get_str("Mother Genetic File")),
+		genetics_file_father: genetics_file_name(tag.get_str("Father Genetic File")),
+		sprite_file_male: tag.get_str("Egg Glyph File").cloned().unwrap_or_default(),
+		sprite_file_female: tag.get_str("Egg Glyph File 2").cloned().unwrap_or_default(),
+		animation_string: tag.get_str("Egg Animation String").cloned().unwrap_or_default(),
+		dependencies: get_dependencies(&tag),
+		extra_int_values: Vec::new(),
+		extra_str_values: Vec::new(),
+		key_order: tag.key_order(),
+		compression
+	};
+
+	let values = egg_values(&egg_block);
+	let is_known = |key: &str| is_dependency_key(key) || tag.is_written(key, &values);
+	egg_block.extra_int_values = tag.extra_int_values(is_known);
+	egg_block.extra_str_values = tag.extra_str_values(is_known);
+
+	Ok(vec![Block::Egg(egg_block)])
+}
+
+// the keys an egg has a field for
+fn is_egg_key(key: &str) -> bool {
+	matches!(key,
+		"Genetics File" |
+		"Mother Genetic File" |
+		"Father Genetic File" |
+		"Egg Glyph File" |
+		"Egg Glyph File 2" |
+		"Egg Animation String")
+}
+
+fn genetics_file_name(file_stem: Option<&String>) -> String {
+	match file_stem {
+		Some(file_name) if !file_name.is_empty() => format!("{}.gen", file_name.trim_end_matches('*')),
+		_ => String::new()
+	}
+}
+
+pub fn write_egg_block(egg_block: &Egg, dependencies: &Vec<File>, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let tag = egg_tag(egg_block, dependencies);
+	let content_buffer = write_tag_block(&tag.int_values, &tag.str_values);
+
+	let is_compressed = options.is_compressed(egg_block.compression, false, content_buffer.len());
+	write_block("EGGS", &egg_block.name, &content_buffer, is_compressed, options.level)
+}
+
+// the values an egg is saved with, including the dependencies it uses
+pub fn egg_tag(egg_block: &Egg, dependencies: &Vec<File>) -> Tag {
+	let mut tag_dependencies: Vec<&File> = Vec::new();
+	for dependency in dependencies {
+		if egg_block.dependencies.contains(&dependency.filename()) {
+			tag_dependencies.push(dependency);
+		}
+	}
+
+	let Tag { mut int_values, mut str_values } = egg_values(egg_block);
+
+	write_dependencies(&mut int_values, &mut str_values, &tag_dependencies);
+
+	write_extra_values(&mut int_values, &mut str_values, &egg_block.extra_int_values, &egg_block.extra_str_values, is_egg_key);
+	restore_key_order(&mut int_values, &mut str_values, &egg_block.key_order);
+
+	Tag { int_values, str_values }
+}
+
+// the values an egg writes from its own fields, leaving out its dependencies
+fn egg_values(egg_block: &Egg) -> Tag {
+	let mut int_values: Vec<(String, u32)> = Vec::new();
+	let mut str_values: Vec<(String, String)> = Vec::new();
+
+	int_values.push(("Agent Type".to_string(), 0));
+
+	str_values.push(("Egg Gallery male".to_string(), file_stem(&egg_block.sprite_file_male)));
+	str_values.push(("Egg Glyph File".to_string(), egg_block.sprite_file_male.clone()));
+
+	str_values.push(("Egg Gallery female".to_string(), file_stem(&egg_block.sprite_file_female)));
+	str_values.push(("Egg Glyph File 2".to_string(), egg_block.sprite_file_female.clone()));
+
+	str_values.push(("Egg Animation String".to_string(), egg_block.animation_string.clone()));
+
+	str_values.push(("Genetics File".to_string(), file_stem(&egg_block.genetics_file)));
+	str_values.push(("Mother Genetic File".to_string(), file_stem(&egg_block.genetics_file_mother)));
+	str_values.push(("Father Genetic File".to_string(), file_stem(&egg_block.genetics_file_father)));
+
+	Tag { int_values, str_values }
+}
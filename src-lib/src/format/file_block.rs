@@ -0,0 +1,61 @@
+use std::{
+	ffi::OsStr,
+	path::{ Path, PathBuf }
+};
+use bytes::Bytes;
+
+use crate::error::Error;
+use super::pray::{
+	Compression,
+	CompressionOptions,
+	write_block,
+};
+
+pub static SUPPORTED_EXTENSIONS: [&str; 10] = ["cos", "wav", "mng", "c16", "s16", "blk", "gen", "gno", "att", "catalogue"];
+
+#[derive(Clone, serde::Serialize)]
+pub struct File {
+	pub name: String,
+	pub extension: String,
+	pub data: Vec<u8>,
+	pub is_checked: bool,
+	pub compression: Compression
+}
+
+impl File {
+	pub fn filename(&self) -> String {
+		format!("{}.{}", self.name, self.extension)
+	}
+}
+
+// file names come from the blocks of whatever file was opened, so any that could point outside the folder are refused,
+// including Windows drive prefixes, which don't count as separators everywhere
+pub fn file_path_in(folder: &Path, file_name: &str) -> Result<PathBuf, Error> {
+	let is_plain = !file_name.contains(['/', '\\', ':']) && Path::new(file_name).file_name() == Some(OsStr::new(file_name));
+	if is_plain {
+		Ok(folder.join(file_name))
+	} else {
+		Err(Error::UnsafeFileName(file_name.to_string()))
+	}
+}
+
+pub fn write_file_block(file_block: &File, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let is_compressed = options.is_compressed(file_block.compression, true, file_block.data.len());
+	write_block("FILE", &file_block.filename(), &file_block.data, is_compressed, options.level)
+}
+
+pub fn sort_dependencies(dependencies: &mut [File]) {
+	dependencies.sort_by_key(|d| (match d.extension.as_str() {
+		"cos" => 0,
+		"gen" => 1,
+		"gno" => 2,
+		"catalogue" => 3,
+		"blk" => 4,
+		"c16" => 5,
+		"s16" => 6,
+		"mng" => 7,
+		"wav" => 8,
+		"att" => 9,
+		_ => 10
+	}, d.name.clone()));
+}
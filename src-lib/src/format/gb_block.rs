@@ -0,0 +1,147 @@
+use bytes::Bytes;
+
+use crate::error::Error;
+use super::cp1252;
+use super::file_block::File;
+use super::agent_block::get_scripts;
+use super::pray::{
+	Block,
+	Tag,
+	Compression,
+	CompressionOptions,
+	read_tag_block,
+	write_block,
+	write_tag_block,
+	get_dependencies,
+	write_dependencies,
+	write_extra_values,
+	restore_key_order,
+	is_dependency_key,
+	is_script_key,
+	file_stem
+};
+
+#[derive(Clone, serde::Serialize)]
+pub struct GardenBox {
+	pub name: String,
+	pub description: String,
+	pub author: String,
+	pub category: u32, // 1: Patch Plant, 2: Traditional Plant, 3: Animal, 4: Aquatic Plant, 5: Aquatic Animal, 6: Decoration, 7: Tools, 8: Misc/Other
+	pub animation_file: String,
+	pub sprite_first_image: u32,
+	pub remove_script: String,
+	pub dependencies: Vec<String>,
+	pub extra_int_values: Vec<(String, u32)>,
+	pub extra_str_values: Vec<(String, String)>,
+	#[serde(skip)]
+	pub key_order: Vec<String>,
+	pub compression: Compression
+}
+
+pub fn read_gb_block(buffer: &mut Bytes, name: &str, compression: Compression) -> Result<Vec<Block>, Error> {
+	let tag = read_tag_block(buffer)?;
+	let mut agent_blocks: Vec<Block> = Vec::new();
+
+	let mut dependencies = get_dependencies(&tag);
+
+	for script_block in get_scripts(&tag, name) {
+		if let Block::File(script) = &script_block {
+			dependencies.push(format!("{}.cos", script.name));
+		}
+		agent_blocks.push(script_block);
+	}
+
+	let mut gb_block = GardenBox {
+		name: name.to_string(),
+		description: tag.get_str("Agent Description").cloned().unwrap_or_default(),
+		author: tag.get_str("Agent Author").cloned().unwrap_or_default(),
+		category: tag.get_int("GB_Category").unwrap_or(0),
+		animation_file: tag.get_str("Agent Animation File").cloned().unwrap_or_default(),
+		sprite_first_image: tag.get_int("Agent Sprite First Image").unwrap_or(0),
+		remove_script: tag.get_str("Remove script").cloned().unwrap_or_default(),
+		dependencies,
+		extra_int_values: Vec::new(),
+		extra_str_values: Vec::new(),
+		key_order: tag.key_order(),
+		compression
+	};
+
+	let values = gb_values(&gb_block);
+	let is_known = |key: &str| is_dependency_key(key) || is_script_key(key) || tag.is_written(key, &values);
+	gb_block.extra_int_values = tag.extra_int_values(is_known);
+	gb_block.extra_str_values = tag.extra_str_values(is_known);
+
+	agent_blocks.push(Block::GardenBox(gb_block));
+
+	Ok(agent_blocks)
+}
+
+pub fn write_gb_block(gb_block: &GardenBox, dependencies: &Vec<File>, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let tag = gb_tag(gb_block, dependencies);
+	let content_buffer = write_tag_block(&tag.int_values, &tag.str_values);
+
+	let is_compressed = options.is_compressed(gb_block.compression, false, content_buffer.len());
+	write_block("DSGB", &gb_block.name, &content_buffer, is_compressed, options.level)
+}
+
+// the values a garden box is saved with, including the dependencies and scripts it uses
+pub fn gb_tag(gb_block: &GardenBox, dependencies: &Vec<File>) -> Tag {
+	let mut tag_scripts: Vec<&File> = Vec::new();
+	let mut tag_dependencies: Vec<&File> = Vec::new();
+	for dependency in dependencies {
+		if gb_block.dependencies.contains(&dependency.filename()) {
+			if dependency.extension == "cos" {
+				tag_scripts.push(dependency);
+			} else {
+				tag_dependencies.push(dependency);
+			}
+		}
+	}
+
+	let Tag { mut int_values, mut str_values } = gb_values(gb_block);
+
+	write_dependencies(&mut int_values, &mut str_values, &tag_dependencies);
+
+	int_values.push(("Script Count".to_string(), tag_scripts.len() as u32));
+	for (i, script) in tag_scripts.iter().enumerate() {
+		str_values.push((format!("Script {}", i + 1), cp1252::decode(&script.data)));
+	}
+
+	write_extra_values(&mut int_values, &mut str_values, &gb_block.extra_int_values, &gb_block.extra_str_values, is_gb_key);
+	restore_key_order(&mut int_values, &mut str_values, &gb_block.key_order);
+
+	Tag { int_values, str_values }
+}
+
+// the values a garden box writes from its own fields, leaving out its dependencies and scripts
+fn gb_values(gb_block: &GardenBox) -> Tag {
+	let mut int_values: Vec<(String, u32)> = Vec::new();
+	let mut str_values: Vec<(String, String)> = Vec::new();
+
+	int_values.push(("Agent Type".to_string(), 0));
+
+	str_values.push(("Agent Description".to_string(), gb_block.description.clone()));
+	str_values.push(("Agent Author".to_string(), gb_block.author.clone()));
+	int_values.push(("GB_Category".to_string(), gb_block.category));
+
+	if !file_stem(&gb_block.animation_file).is_empty() {
+		str_values.push(("Agent Animation Gallery".to_string(), file_stem(&gb_block.animation_file)));
+		str_values.push(("Agent Animation File".to_string(), gb_block.animation_file.clone()));
+		int_values.push(("Agent Sprite First Image".to_string(), gb_block.sprite_first_image));
+	}
+
+	str_values.push(("Remove script".to_string(), gb_block.remove_script.clone()));
+
+	Tag { int_values, str_values }
+}
+
+// the keys a garden box has a field for
+fn is_gb_key(key: &str) -> bool {
+	matches!(key,
+		"Agent Description" |
+		"Agent Author" |
+		"GB_Category" |
+		"Agent Animation File" |
+		"Agent Sprite First Image" |
+		"Remove script")
+}
@@ -0,0 +1,126 @@
+use bytes::{ Bytes, Buf };
+
+use crate::error::Error;
+use super::cp1252;
+use super::genome::Genome;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct GeneNote {
+	pub gene_type: u16,
+	pub subtype: u16,
+	pub id: u16,
+	pub sequence: u16,
+	pub caption: String,
+	pub rich_text: String
+}
+
+impl GeneNote {
+	// notes are usually saved as RTF, which is no good for showing in a list
+	pub fn plain_text(&self) -> String {
+		if self.rich_text.trim_start().starts_with("{\\rtf") {
+			strip_rtf(&self.rich_text)
+		} else {
+			self.rich_text.clone()
+		}
+	}
+}
+
+pub fn decode(contents: &[u8]) -> Result<Vec<GeneNote>, Error> {
+	let mut buffer = Bytes::copy_from_slice(contents);
+	if buffer.remaining() < 2 { return Err(Error::UnexpectedEnd("gene notes")); }
+	let count = buffer.get_u16_le();
+	let mut notes: Vec<GeneNote> = Vec::new();
+	for _ in 0..count {
+		if buffer.remaining() < 8 { return Err(Error::UnexpectedEnd("gene note")); }
+		let gene_type = buffer.get_u16_le();
+		let subtype = buffer.get_u16_le();
+		let id = buffer.get_u16_le();
+		let sequence = buffer.get_u16_le();
+		let caption = read_string(&mut buffer)?;
+		let rich_text = read_string(&mut buffer)?;
+		notes.push(GeneNote { gene_type, subtype, id, sequence, caption, rich_text });
+	}
+	Ok(notes)
+}
+
+fn read_string(buffer: &mut Bytes) -> Result<String, Error> {
+	if buffer.remaining() < 2 { return Err(Error::UnexpectedEnd("gene note text")); }
+	let length = buffer.get_u16_le() as usize;
+	if buffer.remaining() < length { return Err(Error::UnexpectedEnd("gene note text")); }
+	Ok(cp1252::decode(&buffer.copy_to_bytes(length)))
+}
+
+// pairs each gene with its note, matching on type, subtype and id in the order they appear
+pub fn match_notes<'a>(genome: &Genome, notes: &'a [GeneNote]) -> Vec<Option<&'a GeneNote>> {
+	let mut used = vec![false; notes.len()];
+	genome.genes.iter().map(|gene| {
+		let index = notes.iter().enumerate().position(|(i, note)| {
+			!used[i] &&
+				note.gene_type == gene.gene_type as u16 &&
+				note.subtype == gene.subtype as u16 &&
+				note.id == gene.id as u16
+		});
+		index.map(|i| {
+			used[i] = true;
+			&notes[i]
+		})
+	}).collect()
+}
+
+fn strip_rtf(rtf: &str) -> String {
+	let mut text = String::new();
+	let mut chars = rtf.chars().peekable();
+	let mut depth = 0;
+	let mut skip_depth: Option<usize> = None; // inside a group like the font table that isn't text
+	while let Some(c) = chars.next() {
+		match c {
+			'{' => depth += 1,
+			'}' => {
+				if skip_depth == Some(depth) {
+					skip_depth = None;
+				}
+				depth = depth.saturating_sub(1);
+			}
+			'\\' => {
+				let mut word = String::new();
+				while let Some(c) = chars.peek() {
+					if !c.is_ascii_alphabetic() { break; }
+					word.push(*c);
+					chars.next();
+				}
+				if word.is_empty() {
+					match chars.next() {
+						Some('\'') => {
+							let hex: String = chars.by_ref().take(2).collect();
+							if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+								if skip_depth.is_none() { text.push(cp1252::decode_byte(byte)); }
+							}
+						}
+						Some('*') => { skip_depth.get_or_insert(depth); }
+						Some(c) if skip_depth.is_none() => text.push(c),
+						_ => {}
+					}
+					continue;
+				}
+				// numeric parameter, and the space that ends the control word
+				while let Some(c) = chars.peek() {
+					if !(c.is_ascii_digit() || *c == '-') { break; }
+					chars.next();
+				}
+				if chars.peek() == Some(&' ') {
+					chars.next();
+				}
+				match word.as_str() {
+					"fonttbl" | "colortbl" | "stylesheet" | "info" | "pict" => { skip_depth.get_or_insert(depth); }
+					"par" | "line" if skip_depth.is_none() => text.push('\n'),
+					"tab" if skip_depth.is_none() => text.push('\t'),
+					_ => {}
+				}
+			}
+			'\r' | '\n' => {}
+			_ if skip_depth.is_none() => text.push(c),
+			_ => {}
+		}
+	}
+	text.trim().to_string()
+}
@@ -0,0 +1,47 @@
+use bytes::Bytes;
+
+use crate::error::Error;
+use super::pray::{
+	Tag,
+	Compression,
+	CompressionOptions,
+	read_tag_block,
+	write_block,
+	write_tag_block,
+};
+
+#[derive(Clone, serde::Serialize)]
+pub struct GenericBlock {
+	pub id: String,
+	pub name: String,
+	pub data: Vec<u8>,
+	pub values: Option<Tag>, // set when the data is a tag block, so it can be edited as keys and values
+	pub compression: Compression
+}
+
+impl GenericBlock {
+	pub fn new(id: String, name: String, data: Vec<u8>, compression: Compression) -> Self {
+		let values = read_tag_values(&data);
+		GenericBlock { id, name, data, values, compression }
+	}
+
+	pub fn set_values(&mut self, values: Tag) {
+		self.data = write_tag_block(&values.int_values, &values.str_values).to_vec();
+		self.values = Some(values);
+	}
+}
+
+// only counts as a tag block if writing the values back gives the same bytes, so nothing is lost by editing them
+fn read_tag_values(data: &[u8]) -> Option<Tag> {
+	let tag = read_tag_block(&mut Bytes::copy_from_slice(data)).ok()?;
+	if write_tag_block(&tag.int_values, &tag.str_values)[..] == data[..] {
+		Some(tag)
+	} else {
+		None
+	}
+}
+
+pub fn write_generic_block(generic_block: &GenericBlock, options: &CompressionOptions) -> Result<Bytes, Error> {
+	let is_compressed = options.is_compressed(generic_block.compression, false, generic_block.data.len());
+	write_block(&generic_block.id, &generic_block.name, &generic_block.data, is_compressed, options.level)
+}
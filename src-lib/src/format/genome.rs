@@ -0,0 +1,227 @@
+use bytes::{ Bytes, Buf };
+
+use crate::error::Error;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Gene {
+	pub gene_type: u8,
+	pub subtype: u8,
+	pub id: u8,
+	pub generation: u8,
+	pub switch_on: u8, // life stage the gene turns on at
+	pub flags: u8,
+	pub mutability: u8,
+	pub variant: u8,
+	pub payload: Vec<u8>
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct Genome {
+	pub genes: Vec<Gene>
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+	U8(&'static str),
+	U16(&'static str),
+	Chars(&'static str, usize),
+	Raw(&'static str, usize)
+}
+
+use Field::{ U8, U16, Chars, Raw };
+
+const ORGAN: &[Field] = &[U8("Clock Rate"), U8("Damage Rate"), U8("Life Force"), U8("Biotick Start"), U8("ATP Damage Coefficient")];
+
+const LOBE: &[Field] = &[
+	Chars("Lobe ID", 4), U16("Update Time"), U16("X"), U16("Y"), U8("Width"), U8("Height"),
+	U8("Red"), U8("Green"), U8("Blue"), U8("Winner Takes All"), U8("Tissue"), U8("Init Rule Always"),
+	Raw("Spare", 7), Raw("Initialise Rule", 48), Raw("Update Rule", 48)
+];
+
+const TRACT: &[Field] = &[
+	U16("Update Time"),
+	Chars("Source Lobe", 4), U16("Source Lower Bound"), U16("Source Upper Bound"), U16("Source Connections"),
+	Chars("Destination Lobe", 4), U16("Destination Lower Bound"), U16("Destination Upper Bound"), U16("Destination Connections"),
+	U8("Migrates"), U8("Random Connections"), U8("Source Variable"), U8("Destination Variable"), U8("Init Rule Always"),
+	Raw("Spare", 5), Raw("Initialise Rule", 48), Raw("Update Rule", 48)
+];
+
+const RECEPTOR: &[Field] = &[U8("Organ"), U8("Tissue"), U8("Locus"), U8("Chemical"), U8("Threshold"), U8("Nominal"), U8("Gain"), U8("Flags")];
+
+const EMITTER: &[Field] = &[U8("Organ"), U8("Tissue"), U8("Locus"), U8("Chemical"), U8("Threshold"), U8("Rate"), U8("Gain"), U8("Flags")];
+
+const REACTION: &[Field] = &[
+	U8("Reactant 1 Amount"), U8("Reactant 1"), U8("Reactant 2 Amount"), U8("Reactant 2"),
+	U8("Product 1 Amount"), U8("Product 1"), U8("Product 2 Amount"), U8("Product 2"), U8("Rate")
+];
+
+const HALF_LIVES: &[Field] = &[Raw("Half-Lives", 256)];
+
+const INITIAL_CONCENTRATION: &[Field] = &[U8("Chemical"), U8("Amount")];
+
+const NEUROEMITTER: &[Field] = &[
+	U8("Lobe 1"), U8("Neuron 1"), U8("Lobe 2"), U8("Neuron 2"), U8("Lobe 3"), U8("Neuron 3"), U8("Rate"),
+	U8("Chemical 1"), U8("Amount 1"), U8("Chemical 2"), U8("Amount 2"), U8("Chemical 3"), U8("Amount 3"), U8("Chemical 4"), U8("Amount 4")
+];
+
+const STIMULUS: &[Field] = &[
+	U8("Stimulus"), U8("Significance"), U8("Input"), U8("Intensity"), U8("Features"),
+	U8("Chemical 1"), U8("Amount 1"), U8("Chemical 2"), U8("Amount 2"), U8("Chemical 3"), U8("Amount 3"), U8("Chemical 4"), U8("Amount 4")
+];
+
+const GENUS: &[Field] = &[U8("Genus"), Chars("Mother", 32), Chars("Father", 32)];
+
+const APPEARANCE: &[Field] = &[U8("Body Part"), U8("Variant"), U8("Species")];
+
+const POSE: &[Field] = &[U8("Pose"), Chars("Pose String", 16)];
+
+const GAIT: &[Field] = &[U8("Gait"), Chars("Poses", 8)];
+
+const INSTINCT: &[Field] = &[
+	U8("Lobe 1"), U8("Cell 1"), U8("Lobe 2"), U8("Cell 2"), U8("Lobe 3"), U8("Cell 3"),
+	U8("Action"), U8("Reinforcement Chemical"), U8("Reinforcement Amount")
+];
+
+const PIGMENT: &[Field] = &[U8("Pigment"), U8("Amount")];
+
+const PIGMENT_BLEED: &[Field] = &[U8("Rotation"), U8("Swap")];
+
+const FACIAL_EXPRESSION: &[Field] = &[
+	U16("Expression"), U8("Weight"),
+	U8("Drive 1"), U8("Amount 1"), U8("Drive 2"), U8("Amount 2"), U8("Drive 3"), U8("Amount 3"), U8("Drive 4"), U8("Amount 4")
+];
+
+const FLAG_NAMES: [&str; 6] = ["Mutable", "Duplicatable", "Deletable", "Male Only", "Female Only", "Not Expressed"];
+
+const LIFE_STAGES: [&str; 7] = ["Embryo", "Child", "Adolescent", "Youth", "Adult", "Old", "Senile"];
+
+fn gene_kind(gene_type: u8, subtype: u8) -> Option<(&'static str, &'static [Field])> {
+	match (gene_type, subtype) {
+		(0, 0) => Some(("Brain Lobe", LOBE)),
+		(0, 1) => Some(("Brain Organ", ORGAN)),
+		(0, 2) => Some(("Brain Tract", TRACT)),
+		(1, 0) => Some(("Receptor", RECEPTOR)),
+		(1, 1) => Some(("Emitter", EMITTER)),
+		(1, 2) => Some(("Reaction", REACTION)),
+		(1, 3) => Some(("Half-Lives", HALF_LIVES)),
+		(1, 4) => Some(("Initial Concentration", INITIAL_CONCENTRATION)),
+		(1, 5) => Some(("Neuroemitter", NEUROEMITTER)),
+		(2, 0) => Some(("Stimulus", STIMULUS)),
+		(2, 1) => Some(("Genus", GENUS)),
+		(2, 2) => Some(("Appearance", APPEARANCE)),
+		(2, 3) => Some(("Pose", POSE)),
+		(2, 4) => Some(("Gait", GAIT)),
+		(2, 5) => Some(("Instinct", INSTINCT)),
+		(2, 6) => Some(("Pigment", PIGMENT)),
+		(2, 7) => Some(("Pigment Bleed", PIGMENT_BLEED)),
+		(2, 8) => Some(("Facial Expression", FACIAL_EXPRESSION)),
+		(3, 0) => Some(("Organ", ORGAN)),
+		_ => None
+	}
+}
+
+fn payload_size(fields: &[Field]) -> usize {
+	fields.iter().map(|field| match field {
+		U8(_) => 1,
+		U16(_) => 2,
+		Chars(_, size) | Raw(_, size) => *size
+	}).sum()
+}
+
+impl Gene {
+	pub fn kind_name(&self) -> String {
+		match gene_kind(self.gene_type, self.subtype) {
+			Some((name, _)) => name.to_string(),
+			None => format!("Unknown ({} {})", self.gene_type, self.subtype)
+		}
+	}
+
+	pub fn flag_names(&self) -> Vec<&'static str> {
+		FLAG_NAMES.iter().enumerate()
+			.filter(|(i, _)| self.flags & (1 << i) != 0)
+			.map(|(_, name)| *name)
+			.collect()
+	}
+
+	pub fn life_stage(&self) -> String {
+		match LIFE_STAGES.get(self.switch_on as usize) {
+			Some(name) => name.to_string(),
+			None => self.switch_on.to_string()
+		}
+	}
+
+	// the payload split into named values, or the raw bytes if the gene type isn't known
+	// (16-bit values in genomes are big-endian, unlike the rest of the game files)
+	pub fn fields(&self) -> Vec<(String, String)> {
+		let fields = match gene_kind(self.gene_type, self.subtype) {
+			Some((_, fields)) if payload_size(fields) == self.payload.len() => fields,
+			_ => return vec![("Data".to_string(), format_bytes(&self.payload))]
+		};
+		let mut buffer = Bytes::copy_from_slice(&self.payload);
+		fields.iter().map(|field| match *field {
+			U8(name) => (name.to_string(), buffer.get_u8().to_string()),
+			U16(name) => (name.to_string(), buffer.get_u16().to_string()),
+			Chars(name, size) => {
+				let chars = buffer.copy_to_bytes(size);
+				let text: String = chars.iter().take_while(|c| **c != 0).map(|c| *c as char).collect();
+				(name.to_string(), text)
+			}
+			Raw(name, size) => (name.to_string(), format_bytes(&buffer.copy_to_bytes(size)))
+		}).collect()
+	}
+}
+
+fn format_bytes(bytes: &[u8]) -> String {
+	bytes.iter().map(|b| b.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+pub fn decode(contents: &[u8]) -> Result<Genome, Error> {
+	let mut buffer = Bytes::copy_from_slice(contents);
+	if buffer.remaining() < 4 || &buffer[..4] != b"dna3" {
+		return Err(Error::InvalidGenome("Genome is not in the Creatures 3 format".to_string()));
+	}
+	buffer.advance(4);
+
+	let mut genome = Genome::default();
+	loop {
+		if buffer.remaining() < 4 {
+			return Err(Error::UnexpectedEnd("genome"));
+		}
+		let marker = buffer.copy_to_bytes(4);
+		match &marker[..] {
+			b"gend" => break,
+			b"gene" => {}
+			_ => return Err(Error::InvalidGenome(format!("Expected a gene at byte {}", contents.len() - buffer.remaining() - 4)))
+		}
+
+		if buffer.remaining() < 8 {
+			return Err(Error::UnexpectedEnd("gene header"));
+		}
+		let mut gene = Gene {
+			gene_type: buffer.get_u8(),
+			subtype: buffer.get_u8(),
+			id: buffer.get_u8(),
+			generation: buffer.get_u8(),
+			switch_on: buffer.get_u8(),
+			flags: buffer.get_u8(),
+			mutability: buffer.get_u8(),
+			variant: buffer.get_u8(),
+			payload: Vec::new()
+		};
+
+		// unknown genes have no fixed size, so they run up to whatever comes next
+		let size = match gene_kind(gene.gene_type, gene.subtype) {
+			Some((_, fields)) => payload_size(fields),
+			None => buffer.windows(4)
+				.position(|window| window == b"gene" || window == b"gend")
+				.unwrap_or(buffer.remaining())
+		};
+		if buffer.remaining() < size {
+			return Err(Error::UnexpectedEnd("gene"));
+		}
+		gene.payload = buffer.copy_to_bytes(size).to_vec();
+		genome.genes.push(gene);
+	}
+
+	Ok(genome)
+}
@@ -0,0 +1,99 @@
+use bytes::{ Bytes, BytesMut, Buf, BufMut };
+
+use crate::error::Error;
+use super::cp1252;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MngFile {
+	pub script: String,
+	pub samples: Vec<Vec<u8>> // WAV data without the RIFF header
+}
+
+impl MngFile {
+	pub fn track_names(&self) -> Vec<String> {
+		script_names(&self.script, "track")
+	}
+
+	// samples are stored in the order their names first turn up in the script
+	pub fn wave_names(&self) -> Vec<String> {
+		script_names(&self.script, "wave")
+	}
+
+	// a sample with its RIFF header put back, so it can be played or saved as a .wav file
+	pub fn sample_wav(&self, index: usize) -> Option<Vec<u8>> {
+		let sample = self.samples.get(index)?;
+		if sample.starts_with(b"RIFF") {
+			return Some(sample.clone());
+		}
+		let mut buffer = BytesMut::new();
+		buffer.put_slice(b"RIFF");
+		buffer.put_u32_le(sample.len() as u32 + 4);
+		buffer.put_slice(b"WAVE");
+		buffer.put_slice(sample);
+		Some(buffer.to_vec())
+	}
+}
+
+// the script is xored with a key that starts at 5 and goes up by 0xc1 each byte,
+// so scrambling and unscrambling are the same thing
+pub fn scramble(bytes: &[u8]) -> Vec<u8> {
+	let mut key: u8 = 5;
+	bytes.iter().map(|byte| {
+		let scrambled = byte ^ key;
+		key = key.wrapping_add(0xc1);
+		scrambled
+	}).collect()
+}
+
+pub fn decode(contents: &[u8]) -> Result<MngFile, Error> {
+	let mut buffer = Bytes::copy_from_slice(contents);
+	if buffer.remaining() < 12 { return Err(Error::UnexpectedEnd("music header")); }
+	let sample_count = buffer.get_u32_le() as usize;
+	let script_offset = buffer.get_u32_le() as usize;
+	let script_size = buffer.get_u32_le() as usize;
+
+	if buffer.remaining() / 8 < sample_count { return Err(Error::UnexpectedEnd("music sample list")); }
+	let mut samples: Vec<Vec<u8>> = Vec::new();
+	for _ in 0..sample_count {
+		let offset = buffer.get_u32_le() as usize;
+		let size = buffer.get_u32_le() as usize;
+		match contents.get(offset..offset.saturating_add(size)) {
+			Some(sample) => samples.push(sample.to_vec()),
+			None => return Err(Error::UnexpectedEnd("music sample"))
+		}
+	}
+
+	let script = match contents.get(script_offset..script_offset.saturating_add(script_size)) {
+		Some(script) => cp1252::decode(&scramble(script)),
+		None => return Err(Error::UnexpectedEnd("music script"))
+	};
+
+	Ok(MngFile { script, samples })
+}
+
+// finds the names in things like Track(Name) or Wave(Name), ignoring case and duplicates
+fn script_names(script: &str, keyword: &str) -> Vec<String> {
+	let mut names: Vec<String> = Vec::new();
+	for line in script.lines() {
+		let line = line.split("//").next().unwrap_or("");
+		let lowercase_line = line.to_ascii_lowercase();
+		let mut rest = lowercase_line.as_str();
+		let mut position = 0;
+		while let Some(start) = rest.find(keyword) {
+			let after_keyword = position + start + keyword.len();
+			let is_word_start = start == 0 || !rest.as_bytes()[start - 1].is_ascii_alphanumeric();
+			let remainder = line[after_keyword..].trim_start();
+			if is_word_start && remainder.starts_with('(') {
+				if let Some(end) = remainder.find(')') {
+					let name = remainder[1..end].trim().to_string();
+					if !name.is_empty() && !names.contains(&name) {
+						names.push(name);
+					}
+				}
+			}
+			position = after_keyword;
+			rest = &lowercase_line[position..];
+		}
+	}
+	names
+}
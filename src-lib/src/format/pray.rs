@@ -0,0 +1,785 @@
+use std::{
+	io::{ Read, Write },
+	path::{ Path, PathBuf },
+	ffi::OsStr
+};
+use bytes::{ Bytes, BytesMut, Buf, BufMut };
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+
+use crate::error::Error;
+
+use super::cp1252;
+use super::agent_block::{ Agent, GameSupport, read_agent_block, write_agent_block };
+use super::egg_block::{ Egg, read_egg_block, write_egg_block };
+use super::gb_block::{ GardenBox, read_gb_block, write_gb_block };
+use super::file_block::{ File, write_file_block, sort_dependencies };
+use super::generic_block::{ GenericBlock, write_generic_block };
+use super::creature_block::{
+	ExportedCreature,
+	History,
+	CreatureData,
+	Photo,
+	read_export_block,
+	write_export_block,
+	read_history_block,
+	write_history_block,
+	write_history,
+	write_creature_data_block,
+	write_photo_block
+};
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Tag {
+	pub int_values: Vec<(String, u32)>,
+	pub str_values: Vec<(String, String)>
+}
+
+impl Tag {
+	pub fn get_int(&self, key: &str) -> Option<u32> {
+		self.int_values.iter().rev().find(|(k, _)| k == key).map(|(_, v)| *v)
+	}
+
+	pub fn get_str(&self, key: &str) -> Option<&String> {
+		self.str_values.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+	}
+
+	pub fn extra_int_values(&self, is_known: impl Fn(&str) -> bool) -> Vec<(String, u32)> {
+		self.int_values.iter().filter(|(k, _)| !is_known(k)).cloned().collect()
+	}
+
+	pub fn extra_str_values(&self, is_known: impl Fn(&str) -> bool) -> Vec<(String, String)> {
+		self.str_values.iter().filter(|(k, _)| !is_known(k)).cloned().collect()
+	}
+
+	pub fn key_order(&self) -> Vec<String> {
+		self.int_values.iter().map(|(k, _)| k.clone())
+			.chain(self.str_values.iter().map(|(k, _)| k.clone()))
+			.collect()
+	}
+
+	// whether writing a block back gives this key the value it was read with, so it doesn't need keeping as an extra value
+	pub fn is_written(&self, key: &str, written: &Tag) -> bool {
+		written.int_values.iter().any(|(k, v)| k == key && self.get_int(key) == Some(*v)) ||
+			written.str_values.iter().any(|(k, v)| k == key && self.get_str(key) == Some(v))
+	}
+}
+
+#[derive(Clone, serde::Serialize)]
+pub enum Block {
+	File(File),
+	Agent(Agent),
+	Egg(Egg),
+	GardenBox(GardenBox),
+	Export(ExportedCreature),
+	History(History),
+	CreatureData(CreatureData),
+	Photo(Photo),
+	Generic(GenericBlock)
+}
+
+// how a block is stored; blocks read from a file remember how they were, so saving doesn't change them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Compression {
+	#[default]
+	Auto, // decided by the CompressionOptions the file is saved with
+	Stored,
+	Compressed
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CompressionOptions {
+	pub compress_tags: bool,
+	pub compress_files: bool,
+	pub min_file_size: usize, // smaller files are stored as they are, since compressing them only adds to their size
+	pub level: u8 // 0 to 9, as with zlib
+}
+
+impl Default for CompressionOptions {
+	fn default() -> Self {
+		CompressionOptions {
+			compress_tags: false,
+			compress_files: true,
+			min_file_size: 0,
+			level: 6
+		}
+	}
+}
+
+impl CompressionOptions {
+	pub fn is_compressed(&self, compression: Compression, is_file: bool, size: usize) -> bool {
+		match compression {
+			Compression::Stored => false,
+			Compression::Compressed => true,
+			Compression::Auto if is_file => self.compress_files && size >= self.min_file_size,
+			Compression::Auto => self.compress_tags
+		}
+	}
+}
+
+impl Block {
+	pub fn set_compression(&mut self, compression: Compression) {
+		match self {
+			Block::File(b) => b.compression = compression,
+			Block::Agent(b) => b.compression = compression,
+			Block::Egg(b) => b.compression = compression,
+			Block::GardenBox(b) => b.compression = compression,
+			Block::Export(b) => b.compression = compression,
+			Block::History(b) => b.compression = compression,
+			Block::CreatureData(b) => b.compression = compression,
+			Block::Photo(b) => b.compression = compression,
+			Block::Generic(b) => b.compression = compression
+		}
+	}
+
+	// the block id and name it's saved under
+	pub fn key(&self) -> (String, String) {
+		let (id, name) = match self {
+			Block::File(b) => ("FILE", b.filename()),
+			Block::Agent(b) if b.game_support == GameSupport::Creatures3 => ("AGNT", b.name.clone()),
+			Block::Agent(b) => ("DSAG", b.name.clone()),
+			Block::Egg(b) => ("EGGS", b.name.clone()),
+			Block::GardenBox(b) => ("DSGB", b.name.clone()),
+			Block::Export(b) if b.game_support == GameSupport::Creatures3 => ("EXPC", b.name.clone()),
+			Block::Export(b) => ("DSEX", b.name.clone()),
+			Block::History(b) => ("GLST", b.name.clone()),
+			Block::CreatureData(b) => ("CREA", b.name.clone()),
+			Block::Photo(b) => ("PHOT", b.name.clone()),
+			Block::Generic(b) => (b.id.as_str(), b.name.clone())
+		};
+		(id.to_string(), name)
+	}
+}
+
+pub struct BlockHeader {
+	pub id: String,
+	pub name: String,
+	pub size_compressed: usize,
+	pub size_uncompressed: usize,
+	pub is_compressed: bool
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Vec<Block>, Error> {
+	let mut blocks: Vec<Block> = Vec::new();
+	let mut buffer = Bytes::copy_from_slice(bytes);
+	let _file_id = read_string(&mut buffer, 4)?; // should be "PRAY"
+	while !buffer.is_empty() {
+		for block in read_block(&mut buffer)? {
+			blocks.push(block);
+		}
+	}
+	read_history_layouts(&mut blocks);
+	Ok(blocks)
+}
+
+pub fn encode(tags: &Vec<Block>, dependencies: &Vec<File>) -> Result<Bytes, Error> {
+	encode_with_options(tags, dependencies, &CompressionOptions::default())
+}
+
+pub fn encode_with_options(tags: &Vec<Block>, dependencies: &Vec<File>, options: &CompressionOptions) -> Result<Bytes, Error> {
+	encode_in_order(tags, dependencies, options, &[])
+}
+
+// writes the blocks in the given order, by block id and name, with any that aren't in it after the rest
+pub fn encode_in_order(tags: &Vec<Block>, dependencies: &Vec<File>, options: &CompressionOptions, order: &[(String, String)]) -> Result<Bytes, Error> {
+	let keys = block_keys(tags, dependencies);
+	let blocks = write_blocks(tags, dependencies, options)?;
+
+	let mut buffer = BytesMut::new();
+	buffer.extend_from_slice(&write_string("PRAY", 4));
+
+	for i in order_blocks(&keys, order) {
+		buffer.extend_from_slice(&blocks[i]);
+	}
+
+	Ok(buffer.freeze())
+}
+
+// the id and name of each block that would be saved, in the order they'd be saved in
+pub fn block_order(tags: &[Block], dependencies: &[File], order: &[(String, String)]) -> Vec<(String, String)> {
+	let keys = block_keys(tags, dependencies);
+	order_blocks(&keys, order).into_iter().map(|i| keys[i].clone()).collect()
+}
+
+// the id and name of each block in the order write_blocks writes them, without having to write them
+fn block_keys(tags: &[Block], dependencies: &[File]) -> Vec<(String, String)> {
+	tags.iter().map(Block::key)
+		.chain(dependencies.iter().filter(|d| !is_tag_script(tags, d)).map(|d| ("FILE".to_string(), d.filename())))
+		.collect()
+}
+
+// the indexes of the blocks with the given keys, in the given order, then the rest in their own order
+fn order_blocks(keys: &[(String, String)], order: &[(String, String)]) -> Vec<usize> {
+	let mut is_ordered = vec![false; keys.len()];
+	let mut indexes: Vec<usize> = Vec::new();
+	for key in order {
+		if let Some(i) = keys.iter().enumerate().position(|(i, k)| !is_ordered[i] && k == key) {
+			is_ordered[i] = true;
+			indexes.push(i);
+		}
+	}
+	indexes.extend((0..keys.len()).filter(|i| !is_ordered[*i]));
+	indexes
+}
+
+fn write_blocks(tags: &Vec<Block>, dependencies: &Vec<File>, options: &CompressionOptions) -> Result<Vec<Bytes>, Error> {
+	let mut blocks: Vec<Bytes> = Vec::new();
+
+	for tag in tags {
+		match tag {
+			Block::File(file_block) => {
+				blocks.push(write_file_block(file_block, options)?);
+			}
+			Block::Agent(agent_block) => {
+				blocks.push(write_agent_block(agent_block, dependencies, options)?);
+			}
+			Block::Egg(egg_block) => {
+				blocks.push(write_egg_block(egg_block, dependencies, options)?);
+			}
+			Block::GardenBox(gb_block) => {
+				blocks.push(write_gb_block(gb_block, dependencies, options)?);
+			}
+			Block::Export(export_block) => {
+				blocks.push(write_export_block(export_block, options)?);
+			}
+			Block::History(history_block) => {
+				blocks.push(write_history_block(history_block, options)?);
+			}
+			Block::CreatureData(creature_block) => {
+				blocks.push(write_creature_data_block(creature_block, options)?);
+			}
+			Block::Photo(photo_block) => {
+				blocks.push(write_photo_block(photo_block, options)?);
+			}
+			Block::Generic(generic_block) => {
+				blocks.push(write_generic_block(generic_block, options)?);
+			}
+		}
+	}
+
+	// scripts are written into the tags that use them, but a script file that no tag uses is kept as a file
+	for dependency in dependencies {
+		if !is_tag_script(tags, dependency) {
+			blocks.push(write_file_block(dependency, options)?);
+		}
+	}
+
+	Ok(blocks)
+}
+
+pub fn is_tag_script(tags: &[Block], dependency: &File) -> bool {
+	dependency.extension == "cos" && tags.iter().any(|tag| match tag {
+		Block::Agent(agent_block) => agent_block.dependencies.contains(&dependency.filename()),
+		Block::GardenBox(gb_block) => gb_block.dependencies.contains(&dependency.filename()),
+		_ => false
+	})
+}
+
+// files go with the dependencies, sorted as they would be in the editor, and everything else is a tag
+pub fn split_blocks(blocks: Vec<Block>) -> (Vec<Block>, Vec<File>) {
+	let mut tags: Vec<Block> = Vec::new();
+	let mut dependencies: Vec<File> = Vec::new();
+	for block in blocks {
+		match block {
+			Block::File(file) => { dependencies.push(file); }
+			_ => { tags.push(block); }
+		}
+	}
+	sort_dependencies(&mut dependencies);
+	(tags, dependencies)
+}
+
+// the blocks of a file as they were read, so the ones that haven't been changed can be saved exactly as they were,
+// keeping their order, compression and key order
+#[derive(Clone, Default)]
+pub struct Layout {
+	blocks: Vec<LayoutBlock>
+}
+
+impl Layout {
+	// the id and name of each block, in the order they were read
+	pub fn order(&self) -> Vec<(String, String)> {
+		self.blocks.iter().map(|block| (block.id.clone(), block.name.clone())).collect()
+	}
+}
+
+#[derive(Clone)]
+struct LayoutBlock {
+	id: String,
+	name: String,
+	original: Bytes,
+	unchanged: Option<Bytes> // how the block would be written if nothing about it changed
+}
+
+// blocks are compared without compressing them, since only whether they'd be compressed matters
+const UNCHANGED_CHECK: CompressionOptions = CompressionOptions {
+	compress_tags: false,
+	compress_files: true,
+	min_file_size: 0,
+	level: 0
+};
+
+pub fn decode_with_layout(bytes: &[u8]) -> Result<(Vec<Block>, Layout), Error> {
+	let mut blocks: Vec<Block> = Vec::new();
+	let mut originals: Vec<Bytes> = Vec::new();
+	let mut buffer = Bytes::copy_from_slice(bytes);
+	let _file_id = read_string(&mut buffer, 4)?; // should be "PRAY"
+	while !buffer.is_empty() {
+		let block_start = buffer.clone();
+		blocks.extend(read_block(&mut buffer)?);
+		originals.push(block_start.slice(..block_start.len() - buffer.len()));
+	}
+	read_history_layouts(&mut blocks);
+
+	let (tags, dependencies) = split_blocks(blocks.clone());
+	let mut unchanged: Vec<((String, String), Bytes)> = block_keys(&tags, &dependencies).into_iter()
+		.zip(write_blocks(&tags, &dependencies, &UNCHANGED_CHECK)?)
+		.collect();
+
+	let mut layout = Layout::default();
+	for original in originals {
+		let (id, name) = block_key(&original)?;
+		let index = unchanged.iter().position(|((block_id, block_name), _)| *block_id == id && *block_name == name);
+		layout.blocks.push(LayoutBlock {
+			id,
+			name,
+			original,
+			unchanged: index.map(|i| unchanged.remove(i).1)
+		});
+	}
+
+	Ok((blocks, layout))
+}
+
+// blocks that haven't changed are written as they were read, in the given order, with any that aren't in it after the rest
+pub fn encode_with_layout(tags: &Vec<Block>, dependencies: &Vec<File>, options: &CompressionOptions, layout: &Layout, order: &[(String, String)]) -> Result<Bytes, Error> {
+	let current = write_blocks(tags, dependencies, &UNCHANGED_CHECK)?;
+	let keys = block_keys(tags, dependencies);
+	let blocks = write_blocks(tags, dependencies, options)?;
+	let mut is_matched = vec![false; layout.blocks.len()];
+
+	let mut buffer = BytesMut::new();
+	buffer.extend_from_slice(&write_string("PRAY", 4));
+
+	for i in order_blocks(&keys, order) {
+		let (id, name) = &keys[i];
+		let index = layout.blocks.iter().enumerate()
+			.position(|(j, layout_block)| !is_matched[j] && &layout_block.id == id && &layout_block.name == name);
+		match index {
+			Some(j) => {
+				is_matched[j] = true;
+				if layout.blocks[j].unchanged.as_ref() == Some(&current[i]) {
+					buffer.extend_from_slice(&layout.blocks[j].original);
+				} else {
+					buffer.extend_from_slice(&blocks[i]);
+				}
+			}
+			None => buffer.extend_from_slice(&blocks[i])
+		}
+	}
+
+	Ok(buffer.freeze())
+}
+
+// a history that doesn't match the known layout is kept as it is
+fn read_history_or_generic(contents: &Bytes, name: String, game_support: Option<GameSupport>, compression: Compression) -> Block {
+	match read_history_block(contents, &name, game_support, compression) {
+		Ok(history) => Block::History(history),
+		Err(_) => Block::Generic(GenericBlock::new("GLST".to_string(), name, contents.to_vec(), compression))
+	}
+}
+
+// which game a history came from is only known from the export block with the same name, which can come after it,
+// so once every block is read, histories are read again in the layout that game uses
+pub fn read_history_layouts(blocks: &mut [Block]) {
+	let games: Vec<(String, GameSupport)> = blocks.iter().filter_map(|block| match block {
+		Block::Export(export) => Some((export.name.clone(), export.game_support.clone())),
+		_ => None
+	}).collect();
+	for block in blocks.iter_mut() {
+		let (name, contents, compression) = match block {
+			Block::History(history) => (history.name.clone(), write_history(history), history.compression),
+			Block::Generic(generic) if generic.id == "GLST" => (generic.name.clone(), Bytes::from(generic.data.clone()), generic.compression),
+			_ => continue
+		};
+		if let Some((_, game_support)) = games.iter().find(|(export_name, _)| *export_name == name) {
+			*block = read_history_or_generic(&contents, name, Some(game_support.clone()), compression);
+		}
+	}
+}
+
+fn block_key(block: &Bytes) -> Result<(String, String), Error> {
+	let block_header = read_block_header(&mut block.clone())?;
+	Ok((block_header.id, block_header.name))
+}
+
+pub fn read_block(buffer: &mut Bytes) -> Result<Vec<Block>, Error> {
+	let block_header = read_block_header(buffer)?;
+	let block_contents = read_block_contents(buffer, &block_header)?;
+	let compression = if block_header.is_compressed { Compression::Compressed } else { Compression::Stored };
+	read_block_from_contents(block_header.id, block_header.name, block_contents, compression)
+}
+
+pub fn read_block_from_contents(id: String, name: String, mut block_contents: Bytes, compression: Compression) -> Result<Vec<Block>, Error> {
+	match id.as_str() {
+		"FILE" => {
+			let file_path = PathBuf::from(name);
+			let name = file_path.file_stem().unwrap_or(OsStr::new(""));
+			let extension = file_path.extension().unwrap_or(OsStr::new(""));
+			Ok(vec![Block::File(File {
+				name: name.to_str().unwrap_or("").to_string(),
+				extension: extension.to_str().unwrap_or("").to_string(),
+				data: block_contents.to_vec(),
+				is_checked: false,
+				compression
+			})])
+		}
+
+		"AGNT" => {
+			read_agent_block(&mut block_contents, &name, GameSupport::Creatures3, compression)
+		}
+
+		"DSAG" => {
+			read_agent_block(&mut block_contents, &name, GameSupport::DockingStation, compression)
+		}
+
+		"EGGS" => {
+			read_egg_block(&mut block_contents, &name, compression)
+		}
+
+		"DSGB" => {
+			read_gb_block(&mut block_contents, &name, compression)
+		}
+
+		"EXPC" => {
+			read_export_block(&mut block_contents, &name, GameSupport::Creatures3, compression)
+		}
+
+		"DSEX" => {
+			read_export_block(&mut block_contents, &name, GameSupport::DockingStation, compression)
+		}
+
+		// a history that doesn't match the known layout is kept as it is
+		"GLST" => {
+			Ok(vec![read_history_or_generic(&block_contents, name, None, compression)])
+		}
+
+		"CREA" => {
+			Ok(vec![Block::CreatureData(CreatureData {
+				name,
+				data: block_contents.to_vec(),
+				compression
+			})])
+		}
+
+		"PHOT" => {
+			Ok(vec![Block::Photo(Photo {
+				name,
+				data: block_contents.to_vec(),
+				compression
+			})])
+		}
+
+		_ => {
+			Ok(vec![Block::Generic(GenericBlock::new(id, name, block_contents.to_vec(), compression))])
+		}
+	}
+}
+
+pub fn get_dependencies(tag: &Tag) -> Vec<String> {
+	let mut dependencies: Vec<String> = Vec::new();
+	// there can't be more dependencies than text values, however many the count says
+	let num_dependencies = (tag.get_int("Dependency Count").unwrap_or(0) as usize).min(tag.str_values.len());
+	for i in 1..(num_dependencies + 1) {
+		let dependency = tag.get_str(&format!("Dependency {}", i));
+		if let Some(dependecy) = dependency {
+			dependencies.push(dependecy.clone());
+		}
+	}
+	dependencies
+}
+
+pub fn write_dependencies(int_values: &mut Vec<(String, u32)>, str_values: &mut Vec<(String, String)>, tag_dependencies: &[&File]) {
+	int_values.push(("Dependency Count".to_string(), tag_dependencies.len() as u32));
+	for (i, dependency) in tag_dependencies.iter().enumerate() {
+		str_values.push((format!("Dependency {}", i + 1), dependency.filename()));
+		int_values.push((format!("Dependency Category {}", i + 1), match dependency.extension.as_str() {
+			"wav" | "mng" => 1, // Sounds
+			"c16" | "s16" => 2, // Images
+			"gen" | "gno" => 3, // Genetics
+			"att" => 4, // Body Data
+			"blk" => 6, // Backgrounds
+			"catalogue" => 7, // Catalogue
+			_ => 0 // main DS directory
+		}));
+	}
+}
+
+// values a block didn't write back the way they were read are kept as-is, replacing any default written under the same key,
+// except for keys the block has its own field for, which win once the block writes them
+pub fn write_extra_values(int_values: &mut Vec<(String, u32)>, str_values: &mut Vec<(String, String)>, extra_int_values: &[(String, u32)], extra_str_values: &[(String, String)], is_field_key: impl Fn(&str) -> bool) {
+	write_extras(int_values, extra_int_values, &is_field_key);
+	write_extras(str_values, extra_str_values, &is_field_key);
+}
+
+// each value the block wrote is replaced at most once, so a key that was read more than once is written as often
+fn write_extras<T: Clone>(values: &mut Vec<(String, T)>, extra_values: &[(String, T)], is_field_key: impl Fn(&str) -> bool) {
+	let mut is_replaced = vec![false; values.len()];
+	for (key, value) in extra_values {
+		match (0..is_replaced.len()).find(|&i| !is_replaced[i] && values[i].0 == *key) {
+			Some(_) if is_field_key(key) => {}
+			Some(i) => {
+				values[i].1 = value.clone();
+				is_replaced[i] = true;
+			}
+			None => { values.push((key.clone(), value.clone())); }
+		}
+	}
+}
+
+// puts values back in the order they were read in, with any new ones after them
+pub fn restore_key_order(int_values: &mut Vec<(String, u32)>, str_values: &mut Vec<(String, String)>, key_order: &[String]) {
+	sort_by_key_order(int_values, key_order);
+	sort_by_key_order(str_values, key_order);
+}
+
+// the second value with a key goes where the second one was read, and so on
+fn sort_by_key_order<T>(values: &mut Vec<(String, T)>, key_order: &[String]) {
+	let positions: Vec<usize> = (0..values.len()).map(|i| {
+		let key = &values[i].0;
+		let occurrence = values[..i].iter().filter(|(k, _)| k == key).count();
+		key_order.iter().enumerate().filter(|(_, k)| *k == key).nth(occurrence).map(|(position, _)| position).unwrap_or(key_order.len())
+	}).collect();
+	let mut positioned: Vec<(usize, (String, T))> = positions.into_iter().zip(values.drain(..)).collect();
+	positioned.sort_by_key(|(position, _)| *position);
+	values.extend(positioned.into_iter().map(|(_, value)| value));
+}
+
+pub fn is_dependency_key(key: &str) -> bool {
+	key == "Dependency Count" || is_numbered_key(key, "Dependency ") || is_numbered_key(key, "Dependency Category ")
+}
+
+pub fn is_script_key(key: &str) -> bool {
+	key == "Script Count" || is_numbered_key(key, "Script ")
+}
+
+fn is_numbered_key(key: &str, prefix: &str) -> bool {
+	key.strip_prefix(prefix).is_some_and(|n| n.parse::<u32>().is_ok())
+}
+
+pub fn read_block_header(buffer: &mut Bytes) -> Result<BlockHeader, Error> {
+	let id = read_string(buffer, 4)?.to_uppercase();
+	let name = read_string(buffer, 128)?;
+	let size_compressed = read_u32(buffer)? as usize;
+	let size_uncompressed = read_u32(buffer)? as usize;
+	let is_compressed = read_u32(buffer)? == 1;
+	Ok(BlockHeader { id, name, size_compressed, size_uncompressed, is_compressed })
+}
+
+pub fn write_block_header(block_header: BlockHeader) -> Bytes {
+	let mut buffer = BytesMut::new();
+	buffer.extend_from_slice(&write_string(&block_header.id, 4));
+	buffer.extend_from_slice(&write_string(format!("{}\0", block_header.name).as_str(), 128));
+	buffer.put_u32_le(block_header.size_compressed as u32);
+	buffer.put_u32_le(block_header.size_uncompressed as u32);
+	buffer.put_u32_le(if block_header.is_compressed { 1 } else { 0 });
+	buffer.freeze()
+}
+
+pub fn read_block_contents(buffer: &mut Bytes, block_header: &BlockHeader) -> Result<Bytes, Error> {
+	if buffer.len() >= block_header.size_compressed {
+		let mut block_contents = buffer.copy_to_bytes(block_header.size_compressed);
+		if block_header.is_compressed {
+			block_contents = Bytes::from(decompress(&block_contents)?);
+		}
+		Ok(block_contents)
+	} else {
+		Err(Error::UnexpectedEnd("block contents"))
+	}
+}
+
+// far bigger than anything in a real agent, but small enough that a corrupt file can't run the editor out of memory
+pub const MAX_BLOCK_SIZE: usize = 256 * 1024 * 1024;
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, Error> {
+	let mut decoded_data = Vec::new();
+	ZlibDecoder::new(data).take(MAX_BLOCK_SIZE as u64 + 1).read_to_end(&mut decoded_data)?;
+	if decoded_data.len() > MAX_BLOCK_SIZE {
+		return Err(Error::TooLarge(format!("Compressed data unpacks to more than {} MB", MAX_BLOCK_SIZE / 1024 / 1024)));
+	}
+	Ok(decoded_data)
+}
+
+// writes a block header followed by its contents, compressing them first if asked to
+pub fn write_block(id: &str, name: &str, block_contents: &[u8], is_compressed: bool, level: u8) -> Result<Bytes, Error> {
+	let data = if is_compressed { compress_block_contents(block_contents, level)? } else { block_contents.to_vec() };
+	let block_header = BlockHeader {
+		id: id.to_string(),
+		name: name.to_string(),
+		size_compressed: data.len(),
+		size_uncompressed: block_contents.len(),
+		is_compressed
+	};
+	let mut buffer = BytesMut::new();
+	buffer.extend_from_slice(&write_block_header(block_header));
+	buffer.extend_from_slice(&data);
+	Ok(buffer.freeze())
+}
+
+pub fn compress_block_contents(block_contents: &[u8], level: u8) -> Result<Vec<u8>, Error> {
+	let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::new(level.min(9) as u32));
+	encoder.write_all(block_contents)?;
+	Ok(encoder.finish()?)
+}
+
+pub fn read_tag_block(buffer: &mut Bytes) -> Result<Tag, Error> {
+	let mut int_values: Vec<(String, u32)> = Vec::new();
+	// every value takes up at least 8 bytes, so a count that couldn't fit in the rest of the block is corrupt
+	let num_int_values = read_u32(buffer)? as usize;
+	if num_int_values > buffer.len() / 8 { return Err(Error::UnexpectedEnd("a tag's number values")); }
+	for _i in 0..num_int_values {
+		let name_len = read_u32(buffer)?;
+		let name = read_string(buffer, name_len as usize)?;
+		let value = read_u32(buffer)?;
+		int_values.push((name, value));
+	}
+
+	let mut str_values: Vec<(String, String)> = Vec::new();
+	let num_str_values = read_u32(buffer)? as usize;
+	if num_str_values > buffer.len() / 8 { return Err(Error::UnexpectedEnd("a tag's text values")); }
+	for _i in 0..num_str_values {
+		let name_len = read_u32(buffer)?;
+		let name = read_string(buffer, name_len as usize)?;
+		let value_len = read_u32(buffer)?;
+		let value = read_string(buffer, value_len as usize)?;
+		str_values.push((name, value));
+	}
+
+	Ok(Tag{ int_values, str_values })
+}
+
+pub fn write_tag_block(int_values: &[(String, u32)], str_values: &[(String, String)]) -> Bytes {
+	let mut buffer = BytesMut::new();
+
+	buffer.put_u32_le(int_values.len() as u32);
+	for (int_name, int_value) in int_values {
+		let int_name = cp1252::encode(int_name);
+		buffer.put_u32_le(int_name.len() as u32);
+		buffer.extend_from_slice(&int_name);
+		buffer.put_u32_le(*int_value);
+	}
+
+	buffer.put_u32_le(str_values.len() as u32);
+	for (str_name, str_value) in str_values {
+		let str_name = cp1252::encode(str_name);
+		buffer.put_u32_le(str_name.len() as u32);
+		buffer.extend_from_slice(&str_name);
+		let str_value = cp1252::encode(str_value);
+		buffer.put_u32_le(str_value.len() as u32);
+		buffer.extend_from_slice(&str_value);
+	}
+
+	buffer.freeze()
+}
+
+pub fn read_u32(buffer: &mut Bytes) -> Result<u32, Error> {
+	if buffer.len() >= 4 {
+		Ok(buffer.get_u32_le())
+	} else {
+		Err(Error::UnexpectedEnd("an integer"))
+	}
+}
+
+pub fn read_string(buffer: &mut Bytes, str_len: usize) -> Result<String, Error> {
+	if buffer.len() >= str_len {
+		let bytes = buffer.copy_to_bytes(str_len);
+		let bytes: Vec<u8> = bytes.iter().filter(|byte| **byte != 0).copied().collect();
+		Ok(cp1252::decode(&bytes))
+	} else {
+		Err(Error::UnexpectedEnd("a string"))
+	}
+}
+
+pub fn write_string(string: &str, num_bytes: usize) -> Bytes {
+	let mut buffer = BytesMut::new();
+	let bytes = cp1252::encode(string);
+	for i in 0..num_bytes {
+		buffer.put_u8(*bytes.get(i).unwrap_or(&0));
+	}
+	buffer.freeze()
+}
+
+pub fn check_encoding(tags: &[Block], dependencies: &[File]) -> Vec<String> {
+	let mut warnings: Vec<String> = Vec::new();
+
+	for tag in tags {
+		let (name, mut strings): (&String, Vec<&String>) = match tag {
+			Block::Agent(t) => (&t.name, vec![&t.web_label, &t.web_url, &t.animation_file, &t.animation_string, &t.remove_script]),
+			Block::Egg(t) => (&t.name, vec![&t.genetics_file, &t.genetics_file_mother, &t.genetics_file_father, &t.sprite_file_male, &t.sprite_file_female, &t.animation_string]),
+			Block::GardenBox(t) => (&t.name, vec![&t.description, &t.author, &t.animation_file, &t.remove_script]),
+			Block::Export(t) => (&t.name, vec![&t.creature_name, &t.world_name, &t.world_uid]),
+			Block::History(t) => (&t.name, t.events.iter().flat_map(|e| [&e.user_text, &e.world_name]).chain([&t.creature_name]).collect()),
+			Block::CreatureData(t) => (&t.name, Vec::new()),
+			Block::Photo(t) => (&t.name, Vec::new()),
+			Block::File(f) => (&f.name, Vec::new()),
+			Block::Generic(t) => (&t.name, Vec::new())
+		};
+		strings.push(name);
+		match tag {
+			Block::Agent(t) => {
+				strings.extend(t.descriptions.iter().map(|d| &d.text));
+				strings.extend(t.extra_int_values.iter().map(|(k, _)| k));
+				strings.extend(t.extra_str_values.iter().flat_map(|(k, v)| [k, v]));
+			}
+			Block::Egg(t) => {
+				strings.extend(t.extra_int_values.iter().map(|(k, _)| k));
+				strings.extend(t.extra_str_values.iter().flat_map(|(k, v)| [k, v]));
+			}
+			Block::GardenBox(t) => {
+				strings.extend(t.extra_int_values.iter().map(|(k, _)| k));
+				strings.extend(t.extra_str_values.iter().flat_map(|(k, v)| [k, v]));
+			}
+			Block::Export(t) => {
+				strings.extend(t.extra_int_values.iter().map(|(k, _)| k));
+				strings.extend(t.extra_str_values.iter().flat_map(|(k, v)| [k, v]));
+			}
+			Block::Generic(t) => {
+				if let Some(values) = &t.values {
+					strings.extend(values.int_values.iter().map(|(k, _)| k));
+					strings.extend(values.str_values.iter().flat_map(|(k, v)| [k, v]));
+				}
+			}
+			_ => {}
+		}
+		let chars = unencodable_chars(&strings);
+		if !chars.is_empty() {
+			warnings.push(format!("Tag \"{}\": {}", name, chars.iter().collect::<String>()));
+		}
+	}
+
+	for dependency in dependencies {
+		let filename = dependency.filename();
+		let chars = unencodable_chars(&[&filename]);
+		if !chars.is_empty() {
+			warnings.push(format!("File \"{}\": {}", filename, chars.iter().collect::<String>()));
+		}
+	}
+
+	warnings
+}
+
+fn unencodable_chars(strings: &[&String]) -> Vec<char> {
+	let mut chars: Vec<char> = Vec::new();
+	for string in strings {
+		for char in cp1252::unencodable_chars(string) {
+			if !chars.contains(&char) {
+				chars.push(char);
+			}
+		}
+	}
+	chars
+}
+
+pub fn file_stem(file_name: &str) -> String {
+	Path::new(file_name).file_stem().unwrap_or_default().to_str().unwrap_or_default().to_string()
+}
@@ -0,0 +1,366 @@
+use std::{
+	fs,
+	str,
+	path::{ Path, PathBuf },
+	ffi::OsStr
+};
+use bytes::Bytes;
+
+use crate::error::Error;
+
+use super::cp1252;
+use super::file_block::File;
+use super::agent_block::agent_tag;
+use super::egg_block::egg_tag;
+use super::gb_block::gb_tag;
+use super::creature_block::{ export_tag, write_history };
+use super::pray::{
+	Block,
+	Tag,
+	Compression,
+	read_block_from_contents,
+	read_history_layouts,
+	write_tag_block,
+	is_script_key,
+	is_tag_script
+};
+
+enum Token {
+	Word(String),
+	Str(String),
+	Int(u32),
+	At
+}
+
+enum Value {
+	Int(u32),
+	Str(String),
+	FileRef(String)
+}
+
+enum SourceBlock {
+	Group { id: String, name: String, values: Vec<(String, Value)> },
+	Inline { id: String, name: String, file_name: String }
+}
+
+pub fn decode(text: &str, root_path: &Path) -> Result<Vec<Block>, Error> {
+	let source_blocks = parse(text)?;
+	compile(&source_blocks, root_path)
+}
+
+// whether a text file is PRAYsource, which starts with its language, eg. "en-GB"
+pub fn is_source_text(text: &str) -> bool {
+	let first_line = text.trim_start_matches('\u{feff}').lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+	match first_line.strip_prefix('"').and_then(|line| line.strip_suffix('"')) {
+		Some(language) => language.contains('-') && language.chars().all(|c| c.is_ascii_alphabetic() || c == '-'),
+		None => false
+	}
+}
+
+// returns the source text and the files it refers to, which should be written alongside it
+pub fn encode(tags: &Vec<Block>, dependencies: &Vec<File>) -> Result<(String, Vec<File>), Error> {
+	let mut text = String::from("\"en-GB\"\n");
+	let mut files: Vec<File> = Vec::new();
+
+	for tag in tags {
+		let (id, name) = tag.key();
+		text += "\n";
+
+		match tag {
+			Block::Agent(agent_block) => write_group(&mut text, &mut files, &id, &name, &agent_tag(agent_block, dependencies), dependencies),
+			Block::Egg(egg_block) => write_group(&mut text, &mut files, &id, &name, &egg_tag(egg_block, dependencies), dependencies),
+			Block::GardenBox(gb_block) => write_group(&mut text, &mut files, &id, &name, &gb_tag(gb_block, dependencies), dependencies),
+			Block::File(file) => write_inline(&mut text, &mut files, &id, &name, file.clone()),
+			Block::Export(export_block) => {
+				let tag = export_tag(export_block);
+				write_inline(&mut text, &mut files, &id, &name, block_file(&id, &name, write_tag_block(&tag.int_values, &tag.str_values).to_vec()));
+			}
+			Block::History(history) => write_inline(&mut text, &mut files, &id, &name, block_file(&id, &name, write_history(history).to_vec())),
+			Block::CreatureData(creature_data) => write_inline(&mut text, &mut files, &id, &name, block_file(&id, &name, creature_data.data.clone())),
+			Block::Photo(photo) => write_inline(&mut text, &mut files, &id, &name, block_file(&id, &name, photo.data.clone())),
+			Block::Generic(generic_block) => write_inline(&mut text, &mut files, &id, &name, block_file(&id, &name, generic_block.data.clone()))
+		}
+	}
+
+	for dependency in dependencies.iter().filter(|d| !is_tag_script(tags, d)) {
+		text += "\n";
+		write_inline(&mut text, &mut files, "FILE", &dependency.filename(), dependency.clone());
+	}
+
+	Ok((text, files))
+}
+
+fn write_group(text: &mut String, files: &mut Vec<File>, id: &str, name: &str, tag: &Tag, dependencies: &[File]) {
+	*text += &format!("group {} {}\n", id, quote(name));
+	for (key, value) in &tag.int_values {
+		*text += &format!("{} {}\n", quote(key), *value as i32);
+	}
+	for (key, value) in &tag.str_values {
+		let script_file = if is_script_key(key) {
+			dependencies.iter().find(|d| d.extension == "cos" && cp1252::decode(&d.data) == *value)
+		} else {
+			None
+		};
+		match script_file {
+			Some(script_file) => {
+				*text += &format!("{} @ {}\n", quote(key), quote(&script_file.filename()));
+				add_file(files, script_file.clone());
+			}
+			None => {
+				*text += &format!("{} {}\n", quote(key), quote(value));
+			}
+		}
+	}
+}
+
+fn write_inline(text: &mut String, files: &mut Vec<File>, id: &str, name: &str, file: File) {
+	*text += &format!("inline {} {} {}\n", id, quote(name), quote(&file.filename()));
+	add_file(files, file);
+}
+
+// blocks without a file extension are named after their block type instead
+fn block_file(id: &str, name: &str, data: Vec<u8>) -> File {
+	let file_path = PathBuf::from(name);
+	let extension = match file_path.extension() {
+		Some(extension) => extension.to_str().unwrap_or("").to_string(),
+		None => id.to_lowercase()
+	};
+	File {
+		name: file_path.file_stem().unwrap_or(OsStr::new("")).to_str().unwrap_or("").to_string(),
+		extension,
+		data,
+		is_checked: false,
+		compression: Compression::Auto
+	}
+}
+
+fn add_file(files: &mut Vec<File>, file: File) {
+	if !files.iter().any(|f| f.filename() == file.filename()) {
+		files.push(file);
+	}
+}
+
+fn quote(string: &str) -> String {
+	format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// source files and files loaded with "@" may be either UTF-8 or Windows-1252
+pub fn decode_text(bytes: &[u8]) -> String {
+	match str::from_utf8(bytes) {
+		Ok(text) => text.to_string(),
+		Err(_) => cp1252::decode(bytes)
+	}
+}
+
+fn compile(source_blocks: &[SourceBlock], root_path: &Path) -> Result<Vec<Block>, Error> {
+	let mut blocks: Vec<Block> = Vec::new();
+
+	for source_block in source_blocks {
+		let (id, name, block_contents) = match source_block {
+			SourceBlock::Group { id, name, values } => {
+				let mut int_values: Vec<(String, u32)> = Vec::new();
+				let mut str_values: Vec<(String, String)> = Vec::new();
+				for (key, value) in values {
+					match value {
+						Value::Int(value) => int_values.push((key.clone(), *value)),
+						Value::Str(value) => str_values.push((key.clone(), value.clone())),
+						Value::FileRef(file_name) => {
+							let bytes = read_source_file(root_path, file_name)?;
+							str_values.push((key.clone(), decode_text(&bytes)));
+						}
+					}
+				}
+				(id, name, write_tag_block(&int_values, &str_values))
+			}
+			SourceBlock::Inline { id, name, file_name } => {
+				(id, name, Bytes::from(read_source_file(root_path, file_name)?))
+			}
+		};
+
+		blocks.extend(read_block_from_contents(id.clone(), name.clone(), block_contents, Compression::Auto)?);
+	}
+
+	read_history_layouts(&mut blocks);
+	Ok(blocks)
+}
+
+fn read_source_file(root_path: &Path, file_name: &str) -> Result<Vec<u8>, Error> {
+	match fs::read(root_path.join(file_name)) {
+		Ok(bytes) => Ok(bytes),
+		Err(why) => Err(Error::MissingFile { file_name: file_name.to_string(), source: why })
+	}
+}
+
+fn parse(text: &str) -> Result<Vec<SourceBlock>, Error> {
+	let tokens = tokenize(text)?;
+	let mut source_blocks: Vec<SourceBlock> = Vec::new();
+	let mut i = 0;
+
+	// the file may start with a language string, eg. "en-GB"
+	if let Some((Token::Str(_), _)) = tokens.first() {
+		i += 1;
+	}
+
+	while let Some((token, line)) = tokens.get(i) {
+		match token {
+			Token::Word(word) if word == "group" => {
+				let id = expect_block_id(&tokens, i + 1, *line)?;
+				let name = expect_str(&tokens, i + 2, *line)?;
+				i += 3;
+				let mut values: Vec<(String, Value)> = Vec::new();
+				while let Some((Token::Str(key), line)) = tokens.get(i) {
+					match tokens.get(i + 1) {
+						Some((Token::Int(value), _)) => {
+							values.push((key.clone(), Value::Int(*value)));
+							i += 2;
+						}
+						Some((Token::Str(value), _)) => {
+							values.push((key.clone(), Value::Str(value.clone())));
+							i += 2;
+						}
+						Some((Token::At, _)) => {
+							let file_name = expect_str(&tokens, i + 2, *line)?;
+							values.push((key.clone(), Value::FileRef(file_name)));
+							i += 3;
+						}
+						_ => {
+							return Err(Error::Syntax { line: *line, message: format!("Expected a value for \"{}\"", key) });
+						}
+					}
+				}
+				source_blocks.push(SourceBlock::Group { id, name, values });
+			}
+
+			Token::Word(word) if word == "inline" => {
+				let id = expect_block_id(&tokens, i + 1, *line)?;
+				let name = expect_str(&tokens, i + 2, *line)?;
+				let file_name = expect_str(&tokens, i + 3, *line)?;
+				source_blocks.push(SourceBlock::Inline { id, name, file_name });
+				i += 4;
+			}
+
+			_ => {
+				return Err(Error::Syntax { line: *line, message: "Expected \"group\" or \"inline\"".to_string() });
+			}
+		}
+	}
+
+	Ok(source_blocks)
+}
+
+fn expect_block_id(tokens: &[(Token, usize)], i: usize, line: usize) -> Result<String, Error> {
+	match tokens.get(i) {
+		Some((Token::Word(id), _)) if id.len() == 4 => Ok(id.clone()),
+		Some((_, line)) => Err(Error::Syntax { line: *line, message: "Expected a four-letter block type".to_string() }),
+		None => Err(Error::Syntax { line, message: "Expected a four-letter block type".to_string() })
+	}
+}
+
+fn expect_str(tokens: &[(Token, usize)], i: usize, line: usize) -> Result<String, Error> {
+	match tokens.get(i) {
+		Some((Token::Str(string), _)) => Ok(string.clone()),
+		Some((_, line)) => Err(Error::Syntax { line: *line, message: "Expected a quoted string".to_string() }),
+		None => Err(Error::Syntax { line, message: "Expected a quoted string".to_string() })
+	}
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, Error> {
+	let mut tokens: Vec<(Token, usize)> = Vec::new();
+	let chars: Vec<char> = text.chars().collect();
+	let mut line = 1;
+	let mut i = 0;
+
+	while let Some(char) = chars.get(i) {
+		match char {
+			'\n' => {
+				line += 1;
+				i += 1;
+			}
+
+			c if c.is_whitespace() => {
+				i += 1;
+			}
+
+			'(' if chars.get(i + 1) == Some(&'-') => {
+				let start_line = line;
+				i += 2;
+				loop {
+					match chars.get(i) {
+						Some('-') if chars.get(i + 1) == Some(&')') => {
+							i += 2;
+							break;
+						}
+						Some(c) => {
+							if *c == '\n' { line += 1; }
+							i += 1;
+						}
+						None => {
+							return Err(Error::Syntax { line: start_line, message: "Comment is never closed".to_string() });
+						}
+					}
+				}
+			}
+
+			'"' => {
+				let start_line = line;
+				let mut string = String::new();
+				i += 1;
+				loop {
+					match chars.get(i) {
+						Some('"') => {
+							i += 1;
+							break;
+						}
+						Some('\\') => {
+							match chars.get(i + 1) {
+								Some('n') => string.push('\n'),
+								Some('r') => string.push('\r'),
+								Some('t') => string.push('\t'),
+								Some(c) => string.push(*c),
+								None => {}
+							}
+							i += 2;
+						}
+						Some(c) => {
+							if *c == '\n' { line += 1; }
+							string.push(*c);
+							i += 1;
+						}
+						None => {
+							return Err(Error::Syntax { line: start_line, message: "String is never closed".to_string() });
+						}
+					}
+				}
+				tokens.push((Token::Str(string), start_line));
+			}
+
+			'@' => {
+				tokens.push((Token::At, line));
+				i += 1;
+			}
+
+			_ => {
+				let mut word = String::new();
+				while let Some(c) = chars.get(i) {
+					if c.is_whitespace() || *c == '"' || *c == '@' { break; }
+					word.push(*c);
+					i += 1;
+				}
+				if word.starts_with(|c: char| c == '-' || c.is_ascii_digit()) {
+					match word.parse::<i64>() {
+						Ok(value) if value >= i32::MIN as i64 && value <= u32::MAX as i64 => {
+							tokens.push((Token::Int(value as u32), line));
+						}
+						_ => {
+							return Err(Error::Syntax { line, message: format!("Invalid number \"{}\"", word) });
+						}
+					}
+				} else {
+					tokens.push((Token::Word(word), line));
+				}
+			}
+		}
+	}
+
+	Ok(tokens)
+}
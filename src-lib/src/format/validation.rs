@@ -0,0 +1,208 @@
+use crate::sprite::{ animation, decode_sprite };
+
+use super::pray::{ Block, check_encoding };
+use super::catalogue;
+use super::attachment;
+use super::wav;
+use super::cp1252;
+use super::file_block::File;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub enum Severity {
+	Error,
+	Warning
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Issue {
+	pub severity: Severity,
+	pub tag: String,
+	pub field: String,
+	pub message: String
+}
+
+impl Issue {
+	fn error(tag: &str, field: &str, message: String) -> Self {
+		Issue { severity: Severity::Error, tag: tag.to_string(), field: field.to_string(), message }
+	}
+
+	fn warning(tag: &str, field: &str, message: String) -> Self {
+		Issue { severity: Severity::Warning, tag: tag.to_string(), field: field.to_string(), message }
+	}
+}
+
+pub fn has_errors(issues: &[Issue]) -> bool {
+	issues.iter().any(|issue| issue.severity == Severity::Error)
+}
+
+// looks for problems that would make the agent fail to install or look wrong in game
+pub fn validate(tags: &[Block], dependencies: &[File]) -> Vec<Issue> {
+	let mut issues: Vec<Issue> = Vec::new();
+
+	// blocks of different types can share a name, like the blocks of an exported creature, which are all named after its moniker
+	let mut keys: Vec<(String, String)> = Vec::new();
+	for tag in tags {
+		let name = match tag {
+			Block::Agent(t) => &t.name,
+			Block::Egg(t) => &t.name,
+			Block::GardenBox(t) => &t.name,
+			Block::Export(t) => &t.name,
+			Block::History(t) => &t.name,
+			Block::CreatureData(t) => &t.name,
+			Block::Photo(t) => &t.name,
+			Block::File(f) => &f.name,
+			Block::Generic(t) => &t.name
+		};
+		let key = tag.key();
+		if name.trim().is_empty() {
+			issues.push(Issue::error(name, "Name", "Tag has no name".to_string()));
+		} else if keys.contains(&key) {
+			issues.push(Issue::error(name, "Name", format!("More than one {} tag is named \"{}\"", key.0, name)));
+		} else {
+			keys.push(key);
+		}
+	}
+
+	for tag in tags {
+		match tag {
+			Block::Agent(t) => {
+				check_dependency_list(&mut issues, &t.name, &t.dependencies, dependencies);
+				check_animation(&mut issues, &t.name, AnimationProps { field: "Animation File", file: &t.animation_file, string: &t.animation_string, first_image: t.sprite_first_image }, &t.dependencies, dependencies);
+			}
+			Block::Egg(t) => {
+				check_dependency_list(&mut issues, &t.name, &t.dependencies, dependencies);
+				let has_parents = !t.genetics_file_mother.is_empty() && !t.genetics_file_father.is_empty();
+				if t.genetics_file.is_empty() && !has_parents {
+					issues.push(Issue::error(&t.name, "Genetics File", "Egg has no genetics file".to_string()));
+				}
+				for (field, genetics_file) in [("Genetics File", &t.genetics_file), ("Mother's Genetics File", &t.genetics_file_mother), ("Father's Genetics File", &t.genetics_file_father)] {
+					if !genetics_file.is_empty() && !has_genome(genetics_file, dependencies) {
+						issues.push(Issue::warning(&t.name, field, format!("\"{}\" is not one of the agent's files", genetics_file)));
+					}
+				}
+				check_animation(&mut issues, &t.name, AnimationProps { field: "Male Sprite File", file: &t.sprite_file_male, string: &t.animation_string, first_image: 0 }, &t.dependencies, dependencies);
+				check_animation(&mut issues, &t.name, AnimationProps { field: "Female Sprite File", file: &t.sprite_file_female, string: &t.animation_string, first_image: 0 }, &t.dependencies, dependencies);
+			}
+			Block::GardenBox(t) => {
+				check_dependency_list(&mut issues, &t.name, &t.dependencies, dependencies);
+				check_animation(&mut issues, &t.name, AnimationProps { field: "Animation File", file: &t.animation_file, string: "", first_image: t.sprite_first_image }, &t.dependencies, dependencies);
+			}
+			_ => {}
+		}
+	}
+
+	for file in dependencies.iter().filter(|d| d.extension == "catalogue") {
+		match catalogue::parse(&cp1252::decode(&file.data)) {
+			Ok(catalogue) => {
+				for warning in catalogue.check() {
+					issues.push(Issue::warning("", &file.filename(), warning));
+				}
+			}
+			Err(why) => {
+				issues.push(Issue::error("", &file.filename(), why.to_string()));
+			}
+		}
+	}
+
+	for file in dependencies.iter().filter(|d| d.extension == "att") {
+		let points = match attachment::parse(&cp1252::decode(&file.data)) {
+			Ok(points) => points,
+			Err(why) => {
+				issues.push(Issue::error("", &file.filename(), why.to_string()));
+				continue;
+			}
+		};
+		let sprite = dependencies.iter().find(|d| d.extension == "c16" && d.name.eq_ignore_ascii_case(&file.name));
+		if let Some((sprite, Ok(frames))) = sprite.map(|sprite| (sprite, decode_sprite(sprite))) {
+			let frame_sizes: Vec<(u32, u32)> = frames.iter().map(|frame| frame.dimensions()).collect();
+			for warning in attachment::check_frames(&points, &sprite.filename(), &frame_sizes) {
+				issues.push(Issue::warning("", &file.filename(), warning));
+			}
+		}
+	}
+
+	for file in dependencies.iter().filter(|d| d.extension == "wav") {
+		match wav::read_info(&file.data) {
+			Ok(sound) => {
+				for warning in sound.warnings() {
+					issues.push(Issue::warning("", &file.filename(), warning));
+				}
+			}
+			Err(why) => {
+				issues.push(Issue::error("", &file.filename(), why.to_string()));
+			}
+		}
+	}
+
+	for warning in check_encoding(tags, dependencies) {
+		issues.push(Issue::warning("", "", format!("Unsupported characters will be saved as \"?\": {}", warning)));
+	}
+
+	issues
+}
+
+fn check_dependency_list(issues: &mut Vec<Issue>, tag_name: &str, tag_dependencies: &[String], dependencies: &[File]) {
+	for filename in tag_dependencies {
+		if !dependencies.iter().any(|d| &d.filename() == filename) {
+			issues.push(Issue::error(tag_name, "Dependencies", format!("\"{}\" is listed as a dependency but is not in the agent", filename)));
+		}
+	}
+}
+
+struct AnimationProps<'a> {
+	field: &'a str,
+	file: &'a str,
+	string: &'a str,
+	first_image: u32
+}
+
+fn check_animation(issues: &mut Vec<Issue>, tag_name: &str, props: AnimationProps, tag_dependencies: &[String], dependencies: &[File]) {
+	let AnimationProps { field, file: animation_file, string: animation_string, first_image } = props;
+	if animation_file.is_empty() {
+		return;
+	}
+
+	let file = match dependencies.iter().find(|d| d.filename() == animation_file) {
+		Some(file) => file,
+		None => {
+			issues.push(Issue::error(tag_name, field, format!("\"{}\" is not one of the agent's files", animation_file)));
+			return;
+		}
+	};
+
+	if !tag_dependencies.iter().any(|d| d == animation_file) {
+		issues.push(Issue::warning(tag_name, field, format!("\"{}\" is not checked as a dependency of this tag", animation_file)));
+	}
+
+	let frame_count = match decode_sprite(file) {
+		Ok(frames) => frames.len(),
+		Err(why) => {
+			issues.push(Issue::error(tag_name, field, format!("Unable to read images from \"{}\": {}", animation_file, why)));
+			return;
+		}
+	};
+
+	if first_image as usize >= frame_count {
+		issues.push(Issue::error(tag_name, "Sprite First Image", format!("Image {} is past the end of \"{}\", which has {} images", first_image, animation_file, frame_count)));
+		return;
+	}
+
+	match animation::parse(animation_string, first_image) {
+		Ok(animation) => {
+			for message in animation::check_frame_count(&animation, animation_file, frame_count) {
+				issues.push(Issue::error(tag_name, "Animation String", message));
+			}
+		}
+		Err(why) => {
+			issues.push(Issue::error(tag_name, "Animation String", why.to_string()));
+		}
+	}
+}
+
+// genetics files are named without their extension, and the game fills in the rest
+fn has_genome(genetics_file: &str, dependencies: &[File]) -> bool {
+	dependencies.iter().any(|d| {
+		d.extension.eq_ignore_ascii_case("gen") &&
+			(d.name.eq_ignore_ascii_case(genetics_file) || d.filename().eq_ignore_ascii_case(genetics_file))
+	})
+}
@@ -0,0 +1,84 @@
+use bytes::{ Bytes, Buf };
+
+use crate::error::Error;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct WavInfo {
+	pub format_tag: u16, // 1 = PCM
+	pub channels: u16,
+	pub sample_rate: u32,
+	pub bits_per_sample: u16,
+	pub data_size: u32,
+	pub duration_ms: u32
+}
+
+impl WavInfo {
+	// the games hand sounds straight to the mixer, which only copes with plain PCM
+	pub fn warnings(&self) -> Vec<String> {
+		let mut warnings: Vec<String> = Vec::new();
+		if self.format_tag != 1 {
+			warnings.push(format!("Sound is compressed (format {}), but the games can only play uncompressed PCM", self.format_tag));
+		}
+		if self.bits_per_sample != 8 && self.bits_per_sample != 16 {
+			warnings.push(format!("Sound is {}-bit, but the games can only play 8-bit or 16-bit", self.bits_per_sample));
+		}
+		if self.channels > 2 {
+			warnings.push(format!("Sound has {} channels, but the games can only play mono or stereo", self.channels));
+		}
+		if ![11025, 22050, 44100].contains(&self.sample_rate) {
+			warnings.push(format!("Sample rate of {} Hz may play at the wrong speed; use 11025, 22050 or 44100 Hz", self.sample_rate));
+		}
+		warnings
+	}
+}
+
+pub fn read_info(contents: &[u8]) -> Result<WavInfo, Error> {
+	let mut buffer = Bytes::copy_from_slice(contents);
+	if buffer.remaining() < 12 { return Err(Error::UnexpectedEnd("sound header")); }
+	let riff = buffer.copy_to_bytes(4);
+	buffer.advance(4);
+	let wave = buffer.copy_to_bytes(4);
+	if &riff[..] != b"RIFF" || &wave[..] != b"WAVE" {
+		return Err(Error::InvalidSound("Sound is not a WAV file".to_string()));
+	}
+
+	let mut format: Option<(u16, u16, u32, u32, u16)> = None;
+	let mut data_size: Option<u32> = None;
+	while buffer.remaining() >= 8 && (format.is_none() || data_size.is_none()) {
+		let id = buffer.copy_to_bytes(4);
+		let size = buffer.get_u32_le();
+		match &id[..] {
+			b"fmt " => {
+				if size < 16 || buffer.remaining() < 16 { return Err(Error::UnexpectedEnd("sound format")); }
+				let format_tag = buffer.get_u16_le();
+				let channels = buffer.get_u16_le();
+				let sample_rate = buffer.get_u32_le();
+				let byte_rate = buffer.get_u32_le();
+				buffer.advance(2); // block align
+				let bits_per_sample = buffer.get_u16_le();
+				format = Some((format_tag, channels, sample_rate, byte_rate, bits_per_sample));
+				buffer.advance((size as usize - 16).min(buffer.remaining()));
+			}
+			b"data" => {
+				// some tools write a bigger size than the file has room for
+				data_size = Some(size.min(buffer.remaining() as u32));
+				buffer.advance((size as usize).min(buffer.remaining()));
+			}
+			_ => {
+				buffer.advance((size as usize).min(buffer.remaining()));
+			}
+		}
+		// chunks are padded to an even length
+		if size % 2 == 1 && buffer.has_remaining() {
+			buffer.advance(1);
+		}
+	}
+
+	let (format_tag, channels, sample_rate, byte_rate, bits_per_sample) = format
+		.ok_or_else(|| Error::InvalidSound("Sound has no format chunk".to_string()))?;
+	let data_size = data_size
+		.ok_or_else(|| Error::InvalidSound("Sound has no data chunk".to_string()))?;
+	let duration_ms = if byte_rate > 0 { (data_size as u64 * 1000 / byte_rate as u64) as u32 } else { 0 };
+
+	Ok(WavInfo { format_tag, channels, sample_rate, bits_per_sample, data_size, duration_ms })
+}
@@ -0,0 +1,335 @@
+pub mod animation;
+pub mod blk;
+pub mod c16;
+pub mod s16;
+
+use std::{
+	fs,
+	io::BufWriter,
+	path::{ Path, PathBuf }
+};
+use bytes::Bytes;
+use image::{ RgbaImage, Rgba, GenericImage, Frame, Delay };
+use image::codecs::gif::{ GifEncoder, Repeat };
+
+use crate::error::Error;
+use crate::format::file_block::File;
+use crate::format::pray::Compression;
+
+pub fn image_error() -> Error {
+	Error::InvalidSprite("Invalid sprite data".to_string())
+}
+
+// far more than the biggest backgrounds in the games, but small enough that a corrupt header can't run the editor out of memory
+pub const MAX_SPRITE_PIXELS: usize = 1 << 26;
+
+pub fn check_sprite_size(frame_sizes: impl Iterator<Item = (u16, u16)>) -> Result<(), Error> {
+	let pixels: usize = frame_sizes.map(|(width, height)| width as usize * height as usize).sum();
+	if pixels > MAX_SPRITE_PIXELS {
+		return Err(Error::TooLarge(format!("Sprite has {} pixels, more than the {} that can be opened", pixels, MAX_SPRITE_PIXELS)));
+	}
+	Ok(())
+}
+
+pub fn parse_pixel_555(pixel: u16) -> Rgba<u8> {
+	let r = ((pixel & 0x7c00) >> 7) as u8;
+	let g = ((pixel & 0x03e0) >> 2) as u8;
+	let b = ((pixel & 0x001f) << 3) as u8;
+	Rgba([r, g, b, 255])
+}
+
+pub fn parse_pixel_565(pixel: u16) -> Rgba<u8> {
+	let r = ((pixel & 0xf800) >> 8) as u8;
+	let g = ((pixel & 0x07e0) >> 3) as u8;
+	let b = ((pixel & 0x001f) << 3) as u8;
+	Rgba([r, g, b, 255])
+}
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum PixelFormat {
+	Rgb555,
+	Rgb565
+}
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Dithering {
+	None,
+	Ordered,
+	FloydSteinberg
+}
+
+#[derive(Clone, Copy)]
+pub struct EncodeOptions {
+	pub pixel_format: PixelFormat,
+	pub dithering: Dithering
+}
+
+impl Default for EncodeOptions {
+	fn default() -> Self {
+		EncodeOptions {
+			pixel_format: PixelFormat::Rgb565,
+			dithering: Dithering::None
+		}
+	}
+}
+
+static BAYER_MATRIX: [[f32; 4]; 4] = [
+	[ 0.0, 8.0, 2.0, 10.0 ],
+	[ 12.0, 4.0, 14.0, 6.0 ],
+	[ 3.0, 11.0, 1.0, 9.0 ],
+	[ 15.0, 7.0, 13.0, 5.0 ]
+];
+
+// rounds a channel to the nearest level that decodes back to itself, returning the level and the decoded value
+fn quantize_channel(value: f32, bits: u32) -> (u16, f32) {
+	let shift = 8 - bits;
+	let max_level = (1 << bits) - 1;
+	let value = value.round().clamp(0.0, 255.0) as u16;
+	let level = ((value + (1 << (shift - 1))) >> shift).min(max_level);
+	(level, (level << shift) as f32)
+}
+
+// converts a frame to 16-bit pixels, with None for transparent pixels
+pub fn quantize_frame(image: &RgbaImage, options: &EncodeOptions) -> Vec<Option<u16>> {
+	let width = image.width() as usize;
+	let green_bits = match options.pixel_format {
+		PixelFormat::Rgb555 => 5,
+		PixelFormat::Rgb565 => 6
+	};
+	let mut errors: Vec<[f32; 3]> = vec![[0.0; 3]; image.width() as usize * image.height() as usize];
+	let mut pixels: Vec<Option<u16>> = Vec::with_capacity(errors.len());
+	for (x, y, color) in image.enumerate_pixels() {
+		let i = y as usize * width + x as usize;
+		if color[3] < 128 {
+			pixels.push(None);
+			continue;
+		}
+		let mut channels = [color[0] as f32, color[1] as f32, color[2] as f32];
+		match options.dithering {
+			Dithering::None => {}
+			Dithering::Ordered => {
+				let threshold = (BAYER_MATRIX[y as usize % 4][x as usize % 4] + 0.5) / 16.0 - 0.5;
+				channels[0] += threshold * 8.0;
+				channels[1] += threshold * if green_bits == 6 { 4.0 } else { 8.0 };
+				channels[2] += threshold * 8.0;
+			}
+			Dithering::FloydSteinberg => {
+				for (channel, error) in channels.iter_mut().zip(errors[i]) {
+					*channel += error;
+				}
+			}
+		}
+		let (r, r_value) = quantize_channel(channels[0], 5);
+		let (g, g_value) = quantize_channel(channels[1], green_bits);
+		let (b, b_value) = quantize_channel(channels[2], 5);
+		if options.dithering == Dithering::FloydSteinberg {
+			let error = [channels[0] - r_value, channels[1] - g_value, channels[2] - b_value];
+			let x = x as usize;
+			let height = image.height() as usize;
+			let mut spread = |j: usize, weight: f32| {
+				for c in 0..3 {
+					errors[j][c] += error[c] * weight;
+				}
+			};
+			if x + 1 < width { spread(i + 1, 7.0 / 16.0); }
+			if (y as usize) + 1 < height {
+				if x > 0 { spread(i + width - 1, 3.0 / 16.0); }
+				spread(i + width, 5.0 / 16.0);
+				if x + 1 < width { spread(i + width + 1, 1.0 / 16.0); }
+			}
+		}
+		let pixel = match options.pixel_format {
+			PixelFormat::Rgb555 => (r << 10) | (g << 5) | b,
+			PixelFormat::Rgb565 => (r << 11) | (g << 5) | b
+		};
+		pixels.push(Some(pixel));
+	}
+	pixels
+}
+
+pub fn encode_sprite(extension: &str, frames: &[RgbaImage], options: &EncodeOptions) -> Result<Bytes, Error> {
+	match extension {
+		"c16" => c16::encode(frames, options),
+		"s16" => s16::encode(frames, options),
+		"blk" => match frames {
+			[background] => blk::encode(background, options),
+			_ => Err(Error::InvalidSprite("A BLK background must be made from a single image".to_string()))
+		},
+		_ => Err(Error::InvalidSprite(format!("Unable to create .{} sprites", extension)))
+	}
+}
+
+// sorts frames like "sprite_2.png" before "sprite_10.png"
+pub fn sort_frame_paths(paths: &mut [PathBuf]) {
+	paths.sort_by_key(|path| {
+		let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+		let prefix = stem.trim_end_matches(|c: char| c.is_ascii_digit()).to_string();
+		let number: u64 = stem[prefix.len()..].parse().unwrap_or(0);
+		(prefix, number)
+	});
+}
+
+// the sprite name is the first frame's file stem, minus any trailing frame number
+pub fn sprite_name_from_paths(paths: &[PathBuf]) -> String {
+	let stem = paths.first()
+		.and_then(|path| path.file_stem())
+		.unwrap_or_default()
+		.to_string_lossy()
+		.to_string();
+	let name = stem.trim_end_matches(|c: char| c.is_ascii_digit());
+	let name = if paths.len() > 1 { name.trim_end_matches(['_', '-', ' ']) } else { stem.as_str() };
+	if name.is_empty() { stem } else { name.to_string() }
+}
+
+pub fn import_sprite(name: &str, extension: &str, paths: &[PathBuf], options: &EncodeOptions) -> Result<File, Error> {
+	let mut frames: Vec<RgbaImage> = Vec::new();
+	for path in paths {
+		frames.push(image::open(path)?.to_rgba8());
+	}
+	Ok(File {
+		name: name.to_string(),
+		extension: extension.to_string(),
+		data: encode_sprite(extension, &frames, options)?.to_vec(),
+		is_checked: true,
+		compression: Compression::Auto
+	})
+}
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SheetLayout {
+	Horizontal,
+	Grid
+}
+
+#[derive(serde::Serialize)]
+struct SheetManifest {
+	image: String,
+	frame_width: u32,
+	frame_height: u32,
+	frames: Vec<SheetFrame>
+}
+
+#[derive(serde::Serialize)]
+struct SheetFrame {
+	index: usize,
+	x: u32,
+	y: u32,
+	width: u32,
+	height: u32
+}
+
+pub fn decode_sprite(file: &File) -> Result<Vec<RgbaImage>, Error> {
+	match file.extension.as_str() {
+		"c16" => c16::decode(&file.data),
+		"s16" => s16::decode(&file.data),
+		"blk" => blk::decode(&file.data),
+		_ => Err(image_error())
+	}
+}
+
+// picks out the selected frames in sprite order, or all of them if none are selected
+fn selected_frames(frames: Vec<RgbaImage>, frame_indexes: &[usize]) -> Vec<(usize, RgbaImage)> {
+	frames.into_iter()
+		.enumerate()
+		.filter(|(i, _)| frame_indexes.is_empty() || frame_indexes.contains(i))
+		.collect()
+}
+
+pub fn export_sprite(file: &File, path: &Path, frame_indexes: &[usize]) -> Result<(), Error> {
+	let frames = decode_sprite(file)?;
+	for (i, frame) in frames.iter().enumerate() {
+		if frame_indexes.contains(&i) {
+			if let Some(file_name) = path.file_name() {
+				if let Some(file_name) = file_name.to_str() {
+					let new_file_name = match frame_indexes.len() {
+						1 => file_name.to_string(),
+						_ => file_name.replace(".png", &format!("_{}.png", i))
+					};
+					let file_path = path.with_file_name(new_file_name);
+					frame.save(file_path)?;
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+// lays the frames out in equal cells and writes a JSON manifest of where each one ended up
+pub fn export_sprite_sheet(file: &File, path: &Path, frame_indexes: &[usize], layout: SheetLayout) -> Result<(), Error> {
+	let frames = selected_frames(decode_sprite(file)?, frame_indexes);
+	if frames.is_empty() {
+		return Err(Error::InvalidSprite("No frames to export".to_string()));
+	}
+
+	let frame_width = frames.iter().map(|(_, f)| f.width()).max().unwrap_or(0);
+	let frame_height = frames.iter().map(|(_, f)| f.height()).max().unwrap_or(0);
+	let columns = match layout {
+		SheetLayout::Horizontal => frames.len() as u32,
+		SheetLayout::Grid => (frames.len() as f64).sqrt().ceil() as u32
+	};
+	let rows = (frames.len() as u32 + columns - 1) / columns;
+
+	let mut sheet = RgbaImage::new(columns * frame_width, rows * frame_height);
+	let mut manifest = SheetManifest {
+		image: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+		frame_width,
+		frame_height,
+		frames: Vec::new()
+	};
+	for (position, (index, frame)) in frames.iter().enumerate() {
+		let x = (position as u32 % columns) * frame_width;
+		let y = (position as u32 / columns) * frame_height;
+		sheet.copy_from(frame, x, y)?;
+		manifest.frames.push(SheetFrame { index: *index, x, y, width: frame.width(), height: frame.height() });
+	}
+
+	sheet.save(path)?;
+	fs::write(path.with_extension("json"), serde_json::to_string_pretty(&manifest)?)?;
+	Ok(())
+}
+
+// writes an animated GIF if the path ends in .gif, otherwise an animated PNG
+pub fn export_animation(file: &File, path: &Path, frame_indexes: &[usize], frame_delay_ms: u32) -> Result<(), Error> {
+	let frames = selected_frames(decode_sprite(file)?, frame_indexes);
+	if frames.is_empty() {
+		return Err(Error::InvalidSprite("No frames to export".to_string()));
+	}
+
+	// frames can differ in size, so draw them all onto a canvas that fits the largest
+	let width = frames.iter().map(|(_, f)| f.width()).max().unwrap_or(0);
+	let height = frames.iter().map(|(_, f)| f.height()).max().unwrap_or(0);
+	let mut canvases: Vec<RgbaImage> = Vec::new();
+	for (_, frame) in frames {
+		let mut canvas = RgbaImage::new(width, height);
+		canvas.copy_from(&frame, 0, 0)?;
+		canvases.push(canvas);
+	}
+
+	let writer = BufWriter::new(fs::File::create(path)?);
+	let is_gif = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("gif"));
+	if is_gif {
+		let mut encoder = GifEncoder::new(writer);
+		encoder.set_repeat(Repeat::Infinite)?;
+		let delay = Delay::from_numer_denom_ms(frame_delay_ms, 1);
+		encoder.encode_frames(canvases.into_iter().map(|canvas| Frame::from_parts(canvas, 0, 0, delay)))?;
+	} else {
+		let mut encoder = png::Encoder::new(writer, width, height);
+		encoder.set_color(png::ColorType::Rgba);
+		encoder.set_depth(png::BitDepth::Eight);
+		encoder.set_animated(canvases.len() as u32, 0)?;
+		encoder.set_frame_delay(frame_delay_ms.min(u16::MAX as u32) as u16, 1000)?;
+		encoder.set_dispose_op(png::DisposeOp::Background)?;
+		let mut writer = encoder.write_header()?;
+		for canvas in canvases {
+			writer.write_image_data(&canvas)?;
+		}
+		writer.finish()?;
+	}
+	Ok(())
+}
+
+pub fn export_background(file: &File, path: &Path) -> Result<(), Error> {
+	let background = blk::decode_background(&file.data)?;
+	background.save(path)?;
+	Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::error::Error;
+
+// the games advance an animation by one image per tick, at 20 ticks per second
+pub static FRAME_DELAY_MS: u32 = 50;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Animation {
+	pub frames: Vec<usize>, // image indexes in the sprite file, already offset by the first image
+	pub loop_start: Option<usize> // position in frames to jump back to after the last frame
+}
+
+// an animation string is a list of poses relative to the first image, optionally ending
+// with R (or 255) to repeat from the start, or R n to repeat from the nth pose
+pub fn parse(animation_string: &str, first_image: u32) -> Result<Animation, Error> {
+	let mut frames: Vec<usize> = Vec::new();
+	let mut loop_start: Option<usize> = None;
+	let mut tokens = animation_string.split_whitespace();
+
+	while let Some(token) = tokens.next() {
+		if token.eq_ignore_ascii_case("r") || token == "255" {
+			if frames.is_empty() {
+				return Err(Error::InvalidAnimation("nothing to repeat before R".to_string()));
+			}
+			let start = match tokens.next() {
+				Some(position) => position.parse::<usize>()
+					.map_err(|_| Error::InvalidAnimation(format!("expected a position after R, found \"{}\"", position)))?,
+				None => 0
+			};
+			if start >= frames.len() {
+				return Err(Error::InvalidAnimation(format!("R {} repeats from past the end of the animation", start)));
+			}
+			if let Some(token) = tokens.next() {
+				return Err(Error::InvalidAnimation(format!("unexpected \"{}\" after R", token)));
+			}
+			loop_start = Some(start);
+		} else {
+			let pose = token.parse::<u8>()
+				.map_err(|_| Error::InvalidAnimation(format!("\"{}\" is not a pose number from 0 to 254", token)))?;
+			frames.push(first_image as usize + pose as usize);
+		}
+	}
+
+	// with no animation, the injector shows the first image by itself
+	if frames.is_empty() {
+		frames.push(first_image as usize);
+	}
+
+	Ok(Animation { frames, loop_start })
+}
+
+pub fn check_frame_count(animation: &Animation, file_name: &str, frame_count: usize) -> Vec<String> {
+	let mut errors: Vec<String> = Vec::new();
+	let mut missing_frames: Vec<usize> = animation.frames.iter()
+		.filter(|frame| **frame >= frame_count)
+		.copied()
+		.collect();
+	missing_frames.sort_unstable();
+	missing_frames.dedup();
+	for frame in missing_frames {
+		errors.push(format!("Image {} is past the end of \"{}\", which has {} images", frame, file_name, frame_count));
+	}
+	errors
+}
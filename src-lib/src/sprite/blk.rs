@@ -0,0 +1,156 @@
+use bytes::{ Bytes, BytesMut, Buf, BufMut };
+use image::{ RgbaImage, GenericImage };
+
+use crate::error::Error;
+use super::{ check_sprite_size, image_error, parse_pixel_555, parse_pixel_565, quantize_frame, EncodeOptions, PixelFormat };
+
+const TILE_SIZE: u32 = 128;
+
+struct FileHeader {
+	pixel_format: u32, // 0 = 555, 1 = 565
+	cols: u16,
+	rows: u16,
+	image_count: u16
+}
+
+struct ImageHeader {
+	first_line_offset: usize,
+	width: u16,
+	height: u16
+}
+
+fn read_file_header(buffer: &mut Bytes) -> Result<FileHeader, Error> {
+	if buffer.remaining() < 10 { return Err(image_error()); }
+	let pixel_format = buffer.get_u32_le();
+	let cols = buffer.get_u16_le();
+	let rows = buffer.get_u16_le();
+	let image_count = buffer.get_u16_le(); // this should equal cols * rows
+	Ok(FileHeader { pixel_format, cols, rows, image_count })
+}
+
+fn read_image_header(buffer: &mut Bytes) -> Result<ImageHeader, Error> {
+	if buffer.remaining() < 8 { return Err(image_error()); }
+	let first_line_offset = buffer.get_u32_le() as usize + 4;
+	let width = buffer.get_u16_le();
+	let height = buffer.get_u16_le();
+	if width != 128 || height != 128 {
+		return Err(Error::InvalidSprite("Invalid data. All frames in a BLK file must be 128 x 128 px.".to_string()));
+	}
+	Ok(ImageHeader {
+		width,
+		height,
+		first_line_offset
+	})
+}
+
+fn read_image_data(contents: &Bytes, header: &ImageHeader, pixel_format: u32) -> Result<RgbaImage, Error> {
+	let mut image = RgbaImage::new(header.width as u32, header.height as u32);
+	let offset = header.first_line_offset;
+	let length = header.width as usize * header.height as usize * 2;
+	if offset > contents.len() || contents.len() - offset < length {
+		return Err(Error::UnexpectedEnd("a background tile"));
+	}
+	let mut buffer = contents.slice(offset..offset + length);
+	for y in 0..image.height() {
+		for x in 0..image.width() {
+			let pixel_data = buffer.get_u16_le();
+			let color = match pixel_format & 1 {
+				0 => parse_pixel_555(pixel_data),
+				_ => parse_pixel_565(pixel_data)
+			};
+			image.put_pixel(x, y, color);
+		}
+	}
+	Ok(image)
+}
+
+pub fn decode(contents: &[u8]) -> Result<Vec<RgbaImage>, Error> {
+	let contents = Bytes::copy_from_slice(contents);
+	let mut buffer = contents.clone();
+	let file_header = read_file_header(&mut buffer)?;
+	decode_tiles(&contents, &mut buffer, &file_header)
+}
+
+fn decode_tiles(contents: &Bytes, buffer: &mut Bytes, file_header: &FileHeader) -> Result<Vec<RgbaImage>, Error> {
+	let mut frames: Vec<RgbaImage> = Vec::new();
+	let mut image_headers: Vec<ImageHeader> = Vec::new();
+	for _ in 0..file_header.image_count {
+		image_headers.push(read_image_header(buffer)?);
+	}
+	check_sprite_size(image_headers.iter().map(|header| (header.width, header.height)))?;
+	for image_header in image_headers {
+		let image = read_image_data(contents, &image_header, file_header.pixel_format)?;
+		frames.push(image);
+	}
+	Ok(frames)
+}
+
+// stitches the tiles back together, in the column-major order the games use
+pub fn decode_background(contents: &[u8]) -> Result<RgbaImage, Error> {
+	let contents = Bytes::copy_from_slice(contents);
+	let mut buffer = contents.clone();
+	let file_header = read_file_header(&mut buffer)?;
+	if file_header.cols as usize * file_header.rows as usize != file_header.image_count as usize {
+		return Err(Error::InvalidSprite(format!("Invalid data. BLK file has {} tiles but {} cols and {} rows.",
+			file_header.image_count, file_header.cols, file_header.rows)));
+	}
+	check_sprite_size((0..file_header.image_count).map(|_| (TILE_SIZE as u16, TILE_SIZE as u16)))?;
+	let tiles = decode_tiles(&contents, &mut buffer, &file_header)?;
+	if tiles.len() != file_header.cols as usize * file_header.rows as usize {
+		return Err(Error::InvalidSprite(format!("Invalid data. BLK file has {} cols and {} rows but only {} tiles could be read.",
+			file_header.cols, file_header.rows, tiles.len())));
+	}
+	let mut image = RgbaImage::new(file_header.cols as u32 * TILE_SIZE, file_header.rows as u32 * TILE_SIZE);
+	for (i, tile) in tiles.iter().enumerate() {
+		let col = (i / file_header.rows as usize) as u32;
+		let row = (i % file_header.rows as usize) as u32;
+		image.copy_from(tile, col * TILE_SIZE, row * TILE_SIZE)?;
+	}
+	Ok(image)
+}
+
+// slices an image into 128 x 128 tiles, padding the right and bottom edges with black
+pub fn encode(image: &RgbaImage, options: &EncodeOptions) -> Result<Bytes, Error> {
+	let cols = (image.width() + TILE_SIZE - 1) / TILE_SIZE;
+	let rows = (image.height() + TILE_SIZE - 1) / TILE_SIZE;
+	let image_count = cols * rows;
+	if image_count == 0 || image_count > u16::MAX as u32 {
+		return Err(Error::InvalidSprite(format!("Unable to encode a {}x{} background", image.width(), image.height())));
+	}
+
+	let mut background = RgbaImage::from_pixel(cols * TILE_SIZE, rows * TILE_SIZE, image::Rgba([0, 0, 0, 255]));
+	background.copy_from(image, 0, 0)?;
+	let pixels = quantize_frame(&background, options);
+
+	let mut buffer = BytesMut::new();
+	buffer.put_u32_le(match options.pixel_format {
+		PixelFormat::Rgb555 => 0,
+		PixelFormat::Rgb565 => 1
+	});
+	buffer.put_u16_le(cols as u16);
+	buffer.put_u16_le(rows as u16);
+	buffer.put_u16_le(image_count as u16);
+
+	let tile_length = (TILE_SIZE * TILE_SIZE * 2) as usize;
+	let header_size = 10 + image_count as usize * 8;
+	for i in 0..image_count as usize {
+		// offsets are stored 4 bytes short
+		buffer.put_u32_le((header_size + i * tile_length - 4) as u32);
+		buffer.put_u16_le(TILE_SIZE as u16);
+		buffer.put_u16_le(TILE_SIZE as u16);
+	}
+
+	let width = background.width();
+	for col in 0..cols {
+		for row in 0..rows {
+			for y in (row * TILE_SIZE)..((row + 1) * TILE_SIZE) {
+				let line_start = (y * width + col * TILE_SIZE) as usize;
+				for pixel in &pixels[line_start..(line_start + TILE_SIZE as usize)] {
+					buffer.put_u16_le(pixel.unwrap_or(0));
+				}
+			}
+		}
+	}
+
+	Ok(buffer.freeze())
+}
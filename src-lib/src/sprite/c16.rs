@@ -0,0 +1,165 @@
+use bytes::{ Bytes, BytesMut, Buf, BufMut };
+use image::RgbaImage;
+
+use crate::error::Error;
+use super::{ check_sprite_size, image_error, parse_pixel_555, parse_pixel_565, quantize_frame, EncodeOptions, PixelFormat };
+
+struct FileHeader {
+	pixel_format: u32, // 2 = 555, 3 = 565
+	image_count: u16
+}
+
+struct ImageHeader {
+	width: u16,
+	height: u16,
+	line_offsets: Vec<u32>
+}
+
+fn read_file_header(buffer: &mut Bytes) -> Result<FileHeader, Error> {
+	if buffer.remaining() < 6 { return Err(image_error()); }
+	Ok(FileHeader {
+		pixel_format: buffer.get_u32_le(),
+		image_count: buffer.get_u16_le()
+	})
+}
+
+fn read_image_header(buffer: &mut Bytes) -> Result<ImageHeader, Error> {
+	if buffer.remaining() < 8 { return Err(Error::UnexpectedEnd("a sprite frame header")); }
+	let first_line_offset = buffer.get_u32_le();
+	let width = buffer.get_u16_le();
+	let height = buffer.get_u16_le();
+	// the first line's offset comes before the frame size and the rest come after it
+	let mut line_offsets: Vec<u32> = Vec::new();
+	if height > 0 {
+		if buffer.remaining() / 4 < height as usize - 1 { return Err(Error::UnexpectedEnd("a sprite frame header")); }
+		line_offsets.push(first_line_offset);
+		for _ in 1..height {
+			line_offsets.push(buffer.get_u32_le());
+		}
+	}
+	Ok(ImageHeader {
+		width,
+		height,
+		line_offsets
+	})
+}
+
+fn read_image_data(contents: &Bytes, header: &ImageHeader, pixel_format: u32) -> Result<RgbaImage, Error> {
+	let mut image = RgbaImage::new(header.width as u32, header.height as u32);
+	for (y, line_offset) in header.line_offsets.iter().enumerate() {
+		let line_offset = *line_offset as usize;
+		if line_offset > contents.len() {
+			return Err(Error::InvalidSprite(format!("Line {} of a frame starts past the end of the sprite", y)));
+		}
+		let mut buffer = contents.slice(line_offset..);
+		let mut x: u16 = 0;
+		while x < header.width {
+			if buffer.remaining() < 2 { return Err(Error::UnexpectedEnd("a sprite line")); }
+			let run_header = buffer.get_u16_le();
+			let run_type = run_header & 0x1; // 0 = transparent, 1 = color
+			let run_length = (run_header & 0xfffe) >> 1;
+			if run_length == 0 {
+				break; // the line ends early, leaving the rest of it transparent
+			}
+			if run_length > header.width - x {
+				return Err(Error::InvalidSprite(format!("Line {} of a frame is wider than the frame", y)));
+			}
+			// transparent runs are skipped over, since the image starts out transparent
+			if run_type == 1 {
+				if buffer.remaining() < run_length as usize * 2 { return Err(Error::UnexpectedEnd("a sprite line")); }
+				for i in 0..run_length {
+					let pixel_data = buffer.get_u16_le();
+					let color = match pixel_format & 1 {
+						0 => parse_pixel_555(pixel_data),
+						_ => parse_pixel_565(pixel_data)
+					};
+					image.put_pixel((x + i) as u32, y as u32, color);
+				}
+			}
+			x += run_length;
+		}
+	}
+	Ok(image)
+}
+
+pub fn decode(contents: &[u8]) -> Result<Vec<RgbaImage>, Error> {
+	let mut frames: Vec<RgbaImage> = Vec::new();
+	let contents = Bytes::copy_from_slice(contents);
+	let mut buffer = contents.clone();
+	let file_header = read_file_header(&mut buffer)?;
+	let mut image_headers: Vec<ImageHeader> = Vec::new();
+	for _ in 0..file_header.image_count {
+		image_headers.push(read_image_header(&mut buffer)?);
+	}
+	check_sprite_size(image_headers.iter().map(|header| (header.width, header.height)))?;
+	for image_header in image_headers {
+		let image = read_image_data(&contents, &image_header, file_header.pixel_format)?;
+		frames.push(image);
+	}
+	Ok(frames)
+}
+
+fn encode_line(buffer: &mut BytesMut, pixels: &[Option<u16>]) {
+	let mut x = 0;
+	while x < pixels.len() {
+		let is_color = pixels[x].is_some();
+		let mut run_length = 0;
+		while x + run_length < pixels.len() && pixels[x + run_length].is_some() == is_color && run_length < 0x7fff {
+			run_length += 1;
+		}
+		buffer.put_u16_le(((run_length as u16) << 1) | is_color as u16);
+		if is_color {
+			// pure black is drawn as transparent by the games, so nudge opaque black up a step
+			for pixel in pixels[x..(x + run_length)].iter().flatten() {
+				buffer.put_u16_le((*pixel).max(1));
+			}
+		}
+		x += run_length;
+	}
+	buffer.put_u16_le(0);
+}
+
+pub fn encode(frames: &[RgbaImage], options: &EncodeOptions) -> Result<Bytes, Error> {
+	if frames.len() > u16::MAX as usize {
+		return Err(Error::InvalidSprite("Too many frames for one sprite".to_string()));
+	}
+
+	let mut header_size = 6;
+	for frame in frames {
+		if frame.width() == 0 || frame.height() == 0 || frame.width() > u16::MAX as u32 || frame.height() > u16::MAX as u32 {
+			return Err(Error::InvalidSprite(format!("Unable to encode a {}x{} frame", frame.width(), frame.height())));
+		}
+		header_size += 8 + (frame.height() as usize - 1) * 4;
+	}
+
+	let mut image_data = BytesMut::new();
+	let mut line_offsets: Vec<Vec<u32>> = Vec::new();
+	for frame in frames {
+		let pixels = quantize_frame(frame, options);
+		let mut frame_offsets: Vec<u32> = Vec::new();
+		for line in pixels.chunks(frame.width() as usize) {
+			frame_offsets.push((header_size + image_data.len()) as u32);
+			encode_line(&mut image_data, line);
+		}
+		image_data.put_u16_le(0);
+		line_offsets.push(frame_offsets);
+	}
+
+	let mut buffer = BytesMut::with_capacity(header_size + image_data.len());
+	buffer.put_u32_le(match options.pixel_format {
+		PixelFormat::Rgb555 => 2,
+		PixelFormat::Rgb565 => 3
+	});
+	buffer.put_u16_le(frames.len() as u16);
+	for (frame, frame_offsets) in frames.iter().zip(line_offsets) {
+		buffer.put_u32_le(frame_offsets[0]);
+		buffer.put_u16_le(frame.width() as u16);
+		buffer.put_u16_le(frame.height() as u16);
+		for line_offset in &frame_offsets[1..] {
+			buffer.put_u32_le(*line_offset);
+		}
+	}
+	buffer.put(image_data);
+
+	Ok(buffer.freeze())
+}
@@ -0,0 +1,110 @@
+use bytes::{ Bytes, BytesMut, Buf, BufMut };
+use image::RgbaImage;
+
+use crate::error::Error;
+use super::{ check_sprite_size, image_error, parse_pixel_555, parse_pixel_565, quantize_frame, EncodeOptions, PixelFormat };
+
+struct FileHeader {
+	pixel_format: u32, // 0 = 555, 1 = 565
+	image_count: u16
+}
+
+struct ImageHeader {
+	offset: u32,
+	width: u16,
+	height: u16
+}
+
+fn read_file_header(buffer: &mut Bytes) -> Result<FileHeader, Error> {
+	if buffer.remaining() < 6 { return Err(image_error()); }
+	Ok(FileHeader {
+		pixel_format: buffer.get_u32_le(),
+		image_count: buffer.get_u16_le()
+	})
+}
+
+fn read_image_header(buffer: &mut Bytes) -> Result<ImageHeader, Error> {
+	if buffer.remaining() < 8 { return Err(Error::UnexpectedEnd("a sprite frame header")); }
+	let offset = buffer.get_u32_le();
+	let width = buffer.get_u16_le();
+	let height = buffer.get_u16_le();
+	Ok(ImageHeader {
+		offset,
+		width,
+		height
+	})
+}
+
+fn read_image_data(contents: &Bytes, header: &ImageHeader, pixel_format: u32) -> Result<RgbaImage, Error> {
+	let mut image = RgbaImage::new(header.width.into(), header.height.into());
+	let offset = header.offset as usize;
+	let length = header.width as usize * header.height as usize * 2;
+	if offset > contents.len() || contents.len() - offset < length {
+		return Err(Error::UnexpectedEnd("a sprite frame"));
+	}
+	let mut buffer = contents.slice(offset..offset + length);
+	for y in 0..header.height {
+		for x in 0..header.width {
+			let pixel_data = buffer.get_u16_le();
+			let mut color = match pixel_format & 1 {
+				0 => parse_pixel_555(pixel_data),
+				_ => parse_pixel_565(pixel_data)
+			};
+			if color[0] == 0 && color[1] == 0 && color[2] == 0 {
+				color[3] = 0;
+			}
+			image.put_pixel(x.into(), y.into(), color);
+		}
+	}
+	Ok(image)
+}
+
+pub fn decode(contents: &[u8]) -> Result<Vec<RgbaImage>, Error> {
+	let mut frames: Vec<RgbaImage> = Vec::new();
+	let contents = Bytes::copy_from_slice(contents);
+	let mut buffer = contents.clone();
+	let file_header = read_file_header(&mut buffer)?;
+	let mut image_headers: Vec<ImageHeader> = Vec::new();
+	for _ in 0..file_header.image_count {
+		image_headers.push(read_image_header(&mut buffer)?);
+	}
+	check_sprite_size(image_headers.iter().map(|header| (header.width, header.height)))?;
+	for image_header in image_headers {
+		let image = read_image_data(&contents, &image_header, file_header.pixel_format)?;
+		frames.push(image);
+	}
+	Ok(frames)
+}
+
+pub fn encode(frames: &[RgbaImage], options: &EncodeOptions) -> Result<Bytes, Error> {
+	if frames.len() > u16::MAX as usize {
+		return Err(Error::InvalidSprite("Too many frames for one sprite".to_string()));
+	}
+
+	let mut buffer = BytesMut::new();
+	buffer.put_u32_le(match options.pixel_format {
+		PixelFormat::Rgb555 => 0,
+		PixelFormat::Rgb565 => 1
+	});
+	buffer.put_u16_le(frames.len() as u16);
+
+	let mut offset = 6 + frames.len() * 8;
+	for frame in frames {
+		if frame.width() == 0 || frame.height() == 0 || frame.width() > u16::MAX as u32 || frame.height() > u16::MAX as u32 {
+			return Err(Error::InvalidSprite(format!("Unable to encode a {}x{} frame", frame.width(), frame.height())));
+		}
+		buffer.put_u32_le(offset as u32);
+		buffer.put_u16_le(frame.width() as u16);
+		buffer.put_u16_le(frame.height() as u16);
+		offset += frame.width() as usize * frame.height() as usize * 2;
+	}
+
+	for frame in frames {
+		// pure black is drawn as transparent by the games, so nudge opaque black up a step
+		for pixel in quantize_frame(frame, options) {
+			buffer.put_u16_le(pixel.map(|p| p.max(1)).unwrap_or(0));
+		}
+	}
+
+	Ok(buffer.freeze())
+}
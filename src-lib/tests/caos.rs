@@ -0,0 +1,41 @@
+use theist::error::Error;
+use theist::format::caos::{ parse, ReferenceKind };
+
+fn sprite_references(text: &str) -> Vec<String> {
+	parse(text).unwrap().references().into_iter()
+		.filter(|reference| reference.kind == ReferenceKind::Sprite)
+		.map(|reference| reference.name)
+		.collect()
+}
+
+#[test]
+fn text_parts_refer_to_their_font() {
+	let text = "new: comp 2 21 1000 \"board\" 1 0 5000\npat: fixd 1 \"board\" 1 10 10 1 \"chars\"\npat: text 2 \"board\" 2 10 40 1 0 \"bigfont\"\n";
+	assert_eq!(sprite_references(text), vec!["board", "chars", "bigfont"]);
+}
+
+fn syntax_error(text: &str) -> (usize, String) {
+	match parse(text) {
+		Err(Error::Syntax { line, message }) => (line, message),
+		Err(why) => panic!("expected a syntax error, got {}", why),
+		Ok(_) => panic!("expected a syntax error")
+	}
+}
+
+#[test]
+fn unterminated_strings_are_errors() {
+	assert_eq!(syntax_error("inst\nsets va00 \"lamp\nendm\n"), (2, "String is missing its closing quote".to_string()));
+	assert_eq!(syntax_error("inst\nsetv va00 [1 2\nendm\n"), (2, "Byte string is missing its closing bracket".to_string()));
+}
+
+#[test]
+fn remove_script_runs_to_the_end_without_endm() {
+	let caos_file = parse("new: simp 2 21 1000 \"lamp\" 1 0 5000\nrscr\nenum 2 21 1000 kill targ next\n").unwrap();
+	assert_eq!(caos_file.remove_script.unwrap().compact(), "enum 2 21 1000 kill targ next");
+}
+
+#[test]
+fn scripts_cant_be_nested() {
+	assert_eq!(syntax_error("scrp 2 21 1000 1\nscrp 2 21 1000 2\nendm\nendm\n"), (2, "Script starting on line 1 is missing endm".to_string()));
+	assert_eq!(syntax_error("scrp 2 21 1000 1\nkill ownr\n"), (1, "Script is missing endm".to_string()));
+}
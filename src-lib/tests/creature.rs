@@ -0,0 +1,152 @@
+use theist::format::pray::{ Block, decode, encode, split_blocks, write_block, write_tag_block };
+use theist::format::validation::validate;
+use theist::format::creature_block::export_tag;
+
+// an exported creature's blocks, laid out the way each game writes them, all named after the creature's moniker
+
+const MONIKER: &str = "001-fuzz-4pbfr-4sgpd-xfxnm-vtrzf";
+
+fn pray_file(blocks: &[Vec<u8>]) -> Vec<u8> {
+	let mut bytes = b"PRAY".to_vec();
+	for block in blocks {
+		bytes.extend_from_slice(block);
+	}
+	bytes
+}
+
+fn push_string(bytes: &mut Vec<u8>, string: &str) {
+	bytes.extend_from_slice(&(string.len() as u32).to_le_bytes());
+	bytes.extend_from_slice(string.as_bytes());
+}
+
+// Docking Station adds a user and two values to each event, which Creatures 3 doesn't have
+fn history_block(has_network_fields: bool) -> Vec<u8> {
+	write_block("GLST", MONIKER, &history_contents(has_network_fields), false, 0).unwrap().to_vec()
+}
+
+fn history_contents(has_network_fields: bool) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	bytes.extend_from_slice(&1u32.to_le_bytes());
+	push_string(&mut bytes, MONIKER);
+	push_string(&mut bytes, "001-fuzz-4pbfr-4sgpd-xfxnm-00000");
+	push_string(&mut bytes, "Fuzz");
+	for value in [2u32, 1, 0, 1] {
+		bytes.extend_from_slice(&value.to_le_bytes());
+	}
+	for value in [3u32, 1200, 0, 1_000_000_000, 0] {
+		bytes.extend_from_slice(&value.to_le_bytes());
+	}
+	for string in ["", "", "hatched", "", "Garden", "world-uid"] {
+		push_string(&mut bytes, string);
+	}
+	if has_network_fields {
+		push_string(&mut bytes, "someone");
+		bytes.extend_from_slice(&[0; 8]);
+	}
+	bytes.extend_from_slice(&[0; 8]);
+	bytes
+}
+
+fn export_block(id: &str) -> Vec<u8> {
+	let int_values: Vec<(String, u32)> = [("Gender", 2), ("Genus", 1)].iter().map(|(k, v)| (k.to_string(), *v)).collect();
+	let str_values: Vec<(String, String)> = [("Creature Name", "Fuzz"), ("Genome Moniker", MONIKER), ("Head Gallery", "a04a")].iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+	write_block(id, MONIKER, &write_tag_block(&int_values, &str_values), false, 0).unwrap().to_vec()
+}
+
+fn creature(id: &str, has_network_fields: bool) -> Vec<u8> {
+	pray_file(&[
+		export_block(id),
+		write_block("CREA", MONIKER, &[1, 2, 3, 4], false, 0).unwrap().to_vec(),
+		history_block(has_network_fields)
+	])
+}
+
+#[test]
+fn history_is_read_in_either_layout() {
+	for (id, has_network_fields) in [("DSEX", true), ("EXPC", false)] {
+		let blocks = decode(&creature(id, has_network_fields)).unwrap();
+		match blocks.iter().find(|block| matches!(block, Block::History(_))) {
+			Some(Block::History(history)) => {
+				assert_eq!(history.has_network_fields, has_network_fields, "{}", id);
+				assert_eq!(history.events.len(), 1, "{}", id);
+				assert_eq!(history.events[0].user_text, "hatched", "{}", id);
+				assert_eq!(history.events[0].network_user, if has_network_fields { "someone" } else { "" }, "{}", id);
+			}
+			_ => panic!("{} history wasn't read", id)
+		}
+	}
+}
+
+#[test]
+fn history_is_saved_as_it_was_read() {
+	for (id, has_network_fields) in [("DSEX", true), ("EXPC", false)] {
+		let (tags, dependencies) = split_blocks(decode(&creature(id, has_network_fields)).unwrap());
+		let encoded = encode(&tags, &dependencies).unwrap();
+		assert!(encoded.windows(history_block(has_network_fields).len()).any(|block| block == history_block(has_network_fields)), "{}", id);
+	}
+}
+
+// a Creatures 3 history with something after it reads as a Docking Station one too, so the export block decides
+#[test]
+fn history_layout_follows_the_export_block() {
+	let mut contents = history_contents(false);
+	contents.extend_from_slice(&[0; 12]);
+	let history = write_block("GLST", MONIKER, &contents, false, 0).unwrap().to_vec();
+	for blocks in [[export_block("EXPC"), history.clone()], [history.clone(), export_block("EXPC")]] {
+		let blocks = decode(&pray_file(&blocks)).unwrap();
+		match blocks.iter().find(|block| matches!(block, Block::History(_))) {
+			Some(Block::History(history)) => {
+				assert!(!history.has_network_fields);
+				assert_eq!(history.events[0].user_text, "hatched");
+				assert_eq!(history.trailing_data, vec![0; 12]);
+			}
+			_ => panic!("history wasn't read")
+		}
+		let (tags, dependencies) = split_blocks(blocks);
+		let encoded = encode(&tags, &dependencies).unwrap();
+		assert!(encoded.windows(history.len()).any(|block| block == history));
+	}
+}
+
+#[test]
+fn genome_moniker_is_read() {
+	match decode(&creature("DSEX", true)).unwrap().first() {
+		Some(Block::Export(export)) => {
+			assert_eq!(export.genome_moniker, MONIKER);
+			assert!(!export.extra_str_values.iter().any(|(key, _)| key == "Genome Moniker"));
+			assert!(export.extra_str_values.iter().any(|(key, _)| key == "Head Gallery"));
+		}
+		_ => panic!("export wasn't read")
+	}
+}
+
+#[test]
+fn only_blocks_of_the_same_type_need_different_names() {
+	let (tags, dependencies) = split_blocks(decode(&creature("DSEX", true)).unwrap());
+	assert!(validate(&tags, &dependencies).iter().all(|issue| issue.field != "Name"));
+
+	// an agent can come in a Creatures 3 and a Docking Station version with the same name
+	let agent = |id: &str| write_block(id, "Lamp", &write_tag_block(&[("Agent Type".to_string(), 0)], &[]), false, 0).unwrap().to_vec();
+	let (tags, dependencies) = split_blocks(decode(&pray_file(&[agent("AGNT"), agent("DSAG")])).unwrap());
+	assert!(validate(&tags, &dependencies).iter().all(|issue| issue.field != "Name"));
+
+	let (tags, dependencies) = split_blocks(decode(&pray_file(&[export_block("DSEX"), export_block("DSEX")])).unwrap());
+	assert!(validate(&tags, &dependencies).iter().any(|issue| issue.field == "Name"));
+}
+
+// an empty genome moniker isn't written from its field, and repeated keys the editor doesn't know are kept as they were
+#[test]
+fn export_keys_keep_their_order_and_duplicates() {
+	let int_values: Vec<(String, u32)> = [("Genus", 1), ("Gender", 2), ("Variant", 0), ("Creature Life Stage", 0), ("Creature Age In Ticks", 0), ("Pregnancy Status", 0)].iter()
+		.map(|(k, v)| (k.to_string(), *v)).collect();
+	let str_values: Vec<(String, String)> = [("Head Gallery", "a04a"), ("Genome Moniker", ""), ("Head Gallery", "a05a"), ("Creature Name", "Fuzz"), ("Exported From World Name", "Garden"), ("Exported From World UID", "world-uid")].iter()
+		.map(|(k, v)| (k.to_string(), v.to_string())).collect();
+	match decode(&pray_file(&[write_block("DSEX", MONIKER, &write_tag_block(&int_values, &str_values), false, 0).unwrap().to_vec()])).unwrap().first() {
+		Some(Block::Export(export)) => {
+			let tag = export_tag(export);
+			assert_eq!(tag.int_values, int_values);
+			assert_eq!(tag.str_values, str_values);
+		}
+		_ => panic!("export wasn't read")
+	}
+}
@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use theist::format::pray::{ decode, split_blocks, write_block, write_tag_block };
+use theist::format::pray_source;
+use theist::format::file_block::file_path_in;
+
+// scripts are named after the block they came from, so a block can name a file anything
+
+fn hostile_agent() -> Vec<u8> {
+	let int_values = vec![("Script Count".to_string(), 1)];
+	let str_values = vec![("Script 1".to_string(), "inst\nendm\n".to_string())];
+	let mut bytes = b"PRAY".to_vec();
+	bytes.extend_from_slice(&write_block("DSAG", "../../x", &write_tag_block(&int_values, &str_values), false, 0).unwrap());
+	bytes.extend_from_slice(&write_block("FILE", "/tmp/y.wav", b"RIFF", false, 0).unwrap());
+	bytes
+}
+
+#[test]
+fn source_files_named_by_hostile_blocks_are_refused() {
+	let (tags, dependencies) = split_blocks(decode(&hostile_agent()).unwrap());
+	let (_, files) = pray_source::encode(&tags, &dependencies).unwrap();
+	let folder = Path::new("export");
+	let script_file = files.iter().find(|file| file.extension == "cos").unwrap();
+	assert!(file_path_in(folder, &script_file.filename()).is_err());
+
+	// dependencies only keep the last part of their block name
+	let dependency_file = files.iter().find(|file| file.extension == "wav").unwrap();
+	assert_eq!(file_path_in(folder, &dependency_file.filename()).unwrap(), folder.join("y.wav"));
+}
+
+#[test]
+fn only_plain_file_names_are_written() {
+	let folder = Path::new("export");
+	assert_eq!(file_path_in(folder, "crate.cos").unwrap(), folder.join("crate.cos"));
+	assert_eq!(file_path_in(folder, "crate 2.cos").unwrap(), folder.join("crate 2.cos"));
+	for file_name in ["", ".", "..", "../x.cos", "a/b.cos", "a\\b.cos", "..\\x.cos", "/etc/x.cos", "C:x.cos", "C:\\x.cos"] {
+		assert!(file_path_in(folder, file_name).is_err(), "{}", file_name);
+	}
+}
@@ -0,0 +1,199 @@
+use image::RgbaImage;
+
+use theist::error::Error;
+use theist::format::pray::{ Block, decode, decode_with_layout, write_block, write_tag_block };
+use theist::sprite::{ blk, c16, s16, EncodeOptions };
+
+// files cut short or made up of nonsense, the way a bad download might be,
+// which should all come back as errors rather than panicking, hanging or running out of memory
+
+fn c16_sprite(width: u16, height: u16, line_offsets: &[u32], line_data: &[u8]) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	bytes.extend_from_slice(&3u32.to_le_bytes());
+	bytes.extend_from_slice(&1u16.to_le_bytes());
+	bytes.extend_from_slice(&line_offsets.first().copied().unwrap_or(0).to_le_bytes());
+	bytes.extend_from_slice(&width.to_le_bytes());
+	bytes.extend_from_slice(&height.to_le_bytes());
+	for offset in line_offsets.iter().skip(1) {
+		bytes.extend_from_slice(&offset.to_le_bytes());
+	}
+	bytes.extend_from_slice(line_data);
+	bytes
+}
+
+fn s16_sprite(offset: u32, width: u16, height: u16, data: &[u8]) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	bytes.extend_from_slice(&1u32.to_le_bytes());
+	bytes.extend_from_slice(&1u16.to_le_bytes());
+	bytes.extend_from_slice(&offset.to_le_bytes());
+	bytes.extend_from_slice(&width.to_le_bytes());
+	bytes.extend_from_slice(&height.to_le_bytes());
+	bytes.extend_from_slice(data);
+	bytes
+}
+
+fn blk_background(cols: u16, rows: u16, offset: u32) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	bytes.extend_from_slice(&1u32.to_le_bytes());
+	bytes.extend_from_slice(&cols.to_le_bytes());
+	bytes.extend_from_slice(&rows.to_le_bytes());
+	bytes.extend_from_slice(&(cols.wrapping_mul(rows)).to_le_bytes());
+	bytes.extend_from_slice(&offset.to_le_bytes());
+	bytes.extend_from_slice(&128u16.to_le_bytes());
+	bytes.extend_from_slice(&128u16.to_le_bytes());
+	bytes
+}
+
+fn pray_file(blocks: &[Vec<u8>]) -> Vec<u8> {
+	let mut bytes = b"PRAY".to_vec();
+	for block in blocks {
+		bytes.extend_from_slice(block);
+	}
+	bytes
+}
+
+fn agent_block(int_values: &[(&str, u32)], str_values: &[(&str, &str)]) -> Vec<u8> {
+	let int_values: Vec<(String, u32)> = int_values.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+	let str_values: Vec<(String, String)> = str_values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+	write_block("AGNT", "Broken", &write_tag_block(&int_values, &str_values), false, 0).unwrap().to_vec()
+}
+
+#[test]
+fn c16_frame_with_no_height_is_empty() {
+	let frames = c16::decode(&c16_sprite(4, 0, &[], &[])).unwrap();
+	assert_eq!(frames.len(), 1);
+	assert_eq!(frames[0].height(), 0);
+}
+
+#[test]
+fn frame_header_cut_short_is_an_error() {
+	assert!(matches!(c16::decode(&c16_sprite(4, 1000, &[14], &[])), Err(Error::UnexpectedEnd(_))));
+	assert!(matches!(s16::decode(&s16_sprite(14, 4, 4, &[])[..12]), Err(Error::UnexpectedEnd(_))));
+}
+
+#[test]
+fn c16_line_that_ends_early_is_transparent() {
+	let frames = c16::decode(&c16_sprite(4, 1, &[14], &[0, 0])).unwrap();
+	assert!(frames[0].pixels().all(|pixel| pixel[3] == 0));
+}
+
+#[test]
+fn c16_run_wider_than_the_frame_is_an_error() {
+	let run = (5u16 << 1) | 1;
+	let mut line_data = run.to_le_bytes().to_vec();
+	line_data.extend_from_slice(&[0xff; 10]);
+	assert!(c16::decode(&c16_sprite(4, 1, &[14], &line_data)).is_err());
+}
+
+#[test]
+fn c16_colour_run_cut_short_is_an_error() {
+	let run = (4u16 << 1) | 1;
+	let mut line_data = run.to_le_bytes().to_vec();
+	line_data.extend_from_slice(&[0xff; 3]);
+	assert!(c16::decode(&c16_sprite(4, 1, &[14], &line_data)).is_err());
+}
+
+#[test]
+fn c16_line_past_the_end_is_an_error() {
+	assert!(c16::decode(&c16_sprite(4, 2, &[18, u32::MAX], &[0, 0])).is_err());
+}
+
+#[test]
+fn c16_huge_frame_is_refused_before_decoding() {
+	let line_offsets = vec![0; 0xffff];
+	let result = c16::decode(&c16_sprite(0xffff, 0xffff, &line_offsets, &[]));
+	assert!(matches!(result, Err(Error::TooLarge(_))));
+}
+
+#[test]
+fn s16_frame_past_the_end_is_an_error() {
+	assert!(s16::decode(&s16_sprite(14, 4, 4, &[0; 8])).is_err());
+	assert!(s16::decode(&s16_sprite(u32::MAX, 4, 4, &[0; 32])).is_err());
+}
+
+#[test]
+fn frame_with_no_width_or_height_is_not_encoded() {
+	for (width, height) in [(0, 0), (4, 0), (0, 4)] {
+		let frames = [RgbaImage::new(width, height)];
+		assert!(matches!(c16::encode(&frames, &EncodeOptions::default()), Err(Error::InvalidSprite(_))));
+		assert!(matches!(s16::encode(&frames, &EncodeOptions::default()), Err(Error::InvalidSprite(_))));
+	}
+}
+
+#[test]
+fn blk_tile_offset_past_the_end_is_an_error() {
+	assert!(blk::decode(&blk_background(1, 1, u32::MAX)).is_err());
+	assert!(blk::decode_background(&blk_background(1, 1, u32::MAX)).is_err());
+}
+
+#[test]
+fn blk_missing_or_bad_tile_header_is_an_error() {
+	assert!(blk::decode(&blk_background(2, 1, 0)).is_err());
+	assert!(blk::decode_background(&blk_background(2, 1, 0)).is_err());
+
+	let mut background = blk_background(1, 1, 0);
+	background[16..18].copy_from_slice(&64u16.to_le_bytes());
+	assert!(matches!(blk::decode_background(&background), Err(Error::InvalidSprite(_))));
+}
+
+#[test]
+fn blk_huge_background_is_refused_before_decoding() {
+	let result = blk::decode_background(&blk_background(255, 257, 0));
+	assert!(matches!(result, Err(Error::TooLarge(_))));
+}
+
+#[test]
+fn tag_block_with_impossible_counts_is_an_error() {
+	let mut contents = u32::MAX.to_le_bytes().to_vec();
+	contents.extend_from_slice(&0u32.to_le_bytes());
+	let block = write_block("AGNT", "Broken", &contents, false, 0).unwrap().to_vec();
+	assert!(decode(&pray_file(&[block])).is_err());
+
+	let mut contents = 0u32.to_le_bytes().to_vec();
+	contents.extend_from_slice(&u32::MAX.to_le_bytes());
+	let block = write_block("DSAG", "Broken", &contents, true, 6).unwrap().to_vec();
+	assert!(decode(&pray_file(&[block])).is_err());
+}
+
+#[test]
+fn tag_value_with_impossible_length_is_an_error() {
+	let mut contents = 1u32.to_le_bytes().to_vec();
+	contents.extend_from_slice(&u32::MAX.to_le_bytes());
+	contents.extend_from_slice(b"Script Count");
+	let block = write_block("AGNT", "Broken", &contents, false, 0).unwrap().to_vec();
+	assert!(decode(&pray_file(&[block])).is_err());
+}
+
+#[test]
+fn huge_script_and_dependency_counts_only_read_what_is_there() {
+	let block = agent_block(&[
+		("Script Count", u32::MAX),
+		("Dependency Count", u32::MAX)
+	], &[
+		("Script 1", "inst\nendm\n"),
+		("Dependency 1", "broken.c16")
+	]);
+	let blocks = decode(&pray_file(&[block])).unwrap();
+	let scripts = blocks.iter().filter(|block| matches!(block, Block::File(_))).count();
+	assert_eq!(scripts, 1);
+}
+
+#[test]
+fn pray_file_cut_short_is_an_error() {
+	let bytes = pray_file(&[
+		agent_block(&[("Script Count", 1)], &[("Script 1", "inst\nendm\n")]),
+		write_block("FILE", "broken.c16", &[0; 64], true, 6).unwrap().to_vec()
+	]);
+	for length in [2, 10, 100, 150, bytes.len() - 20, bytes.len() - 1] {
+		assert!(decode(&bytes[..length]).is_err(), "cut at {} bytes", length);
+		assert!(decode_with_layout(&bytes[..length]).is_err(), "cut at {} bytes", length);
+	}
+}
+
+#[test]
+fn pray_file_with_broken_compressed_data_is_an_error() {
+	let mut block = write_block("FILE", "broken.c16", &[0; 64], true, 6).unwrap().to_vec();
+	let length = block.len();
+	block[length - 8..].copy_from_slice(&[0xff; 8]);
+	assert!(decode(&pray_file(&[block])).is_err());
+}
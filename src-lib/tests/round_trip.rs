@@ -0,0 +1,481 @@
+use theist::format::pray_source;
+use theist::format::pray::{
+	Block,
+	Compression,
+	CompressionOptions,
+	encode,
+	encode_with_options,
+	encode_with_layout,
+	encode_in_order,
+	block_order,
+	decode,
+	decode_with_layout,
+	split_blocks,
+	read_block_header,
+	read_block_contents,
+	read_tag_block,
+	Tag,
+	write_block,
+	write_tag_block,
+	compress_block_contents,
+	decompress
+};
+
+// a corpus of small agents written the way other tools write them: keys in their own order,
+// tags compressed or not, and compressed blocks made with a different compression level than the editor uses
+
+fn pray_file(blocks: &[Vec<u8>]) -> Vec<u8> {
+	let mut bytes = b"PRAY".to_vec();
+	for block in blocks {
+		bytes.extend_from_slice(block);
+	}
+	bytes
+}
+
+fn tag_block(id: &str, name: &str, int_values: &[(&str, u32)], str_values: &[(&str, &str)], is_compressed: bool) -> Vec<u8> {
+	let int_values: Vec<(String, u32)> = int_values.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+	let str_values: Vec<(String, String)> = str_values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+	let contents = write_tag_block(&int_values, &str_values);
+	write_block(id, name, &contents, is_compressed, 1).unwrap().to_vec()
+}
+
+fn file_block(name: &str, data: &[u8], is_compressed: bool) -> Vec<u8> {
+	write_block("FILE", name, data, is_compressed, 1).unwrap().to_vec()
+}
+
+fn c3_agent() -> Vec<u8> {
+	pray_file(&[
+		tag_block("AGNT", "Hand Mirror", &[
+			("Script Count", 2),
+			("Agent Type", 0),
+			("Dependency Count", 2),
+			("Dependency Category 1", 2),
+			("Dependency Category 2", 1),
+			("Agent Bioenergy Value", 40),
+			("Agent Sprite First Image", 3)
+		], &[
+			("Script 1", "inst\nnew: simp 2 21 1000 \"mirror\" 1 0 0\nendm\n"),
+			("Script 2", "scrp 2 21 1000 1\n\tsndc \"ding\"\nendm\n"),
+			("Remove script", "enum 2 21 1000 kill targ next"),
+			("Agent Animation File", "mirror.c16"),
+			("Agent Animation Gallery", "mirror"),
+			("Agent Animation String", "0"),
+			("Dependency 1", "mirror.c16"),
+			("Dependency 2", "ding.wav"),
+			("Made With", "Some Other Tool")
+		], false),
+		file_block("mirror.c16", &[1, 0, 0, 0, 1, 0, 4, 0, 4, 0].repeat(20), true),
+		file_block("ding.wav", b"RIFF\x04\x00\x00\x00WAVE", false)
+	])
+}
+
+fn ds_agent() -> Vec<u8> {
+	pray_file(&[
+		file_block("backdrop.blk", &[7; 300], true),
+		tag_block("DSAG", "Backdrop", &[
+			("Agent Type", 0),
+			("Script Count", 1),
+			("Dependency Count", 1),
+			("Dependency Category 1", 6)
+		], &[
+			("Agent Description-fr", "Un fond"),
+			("Agent Description", "A backdrop"),
+			("Web Label", "Example"),
+			("Web URL", "example.com"),
+			("Dependency 1", "backdrop.blk"),
+			("Script 1", "inst\nmeta 1 1 1 1\nendm\n"),
+			("Remove script", "")
+		], true)
+	])
+}
+
+fn egg_agent() -> Vec<u8> {
+	pray_file(&[
+		tag_block("EGGS", "Norn Egg", &[
+			("Dependency Count", 2),
+			("Agent Type", 0),
+			("Dependency Category 1", 3),
+			("Dependency Category 2", 3)
+		], &[
+			("Genetics File", "norn.bondi.48"),
+			("Egg Glyph File", "eggs.c16"),
+			("Egg Gallery male", "eggs"),
+			("Egg Glyph File 2", "eggs.c16"),
+			("Egg Gallery female", "eggs"),
+			("Egg Animation String", "0 1 2"),
+			("Mother Genetic File", ""),
+			("Father Genetic File", ""),
+			("Dependency 1", "norn.bondi.48.gen"),
+			("Dependency 2", "norn.bondi.48.gno")
+		], false),
+		file_block("norn.bondi.48.gen", b"dna3gene".repeat(10).as_slice(), true),
+		file_block("norn.bondi.48.gno", &[0, 0], false)
+	])
+}
+
+fn garden_box_agent() -> Vec<u8> {
+	pray_file(&[
+		tag_block("DSGB", "Lily Pad", &[
+			("GB_Category", 4),
+			("Agent Type", 0),
+			("Script Count", 1),
+			("Dependency Count", 0)
+		], &[
+			("Agent Description", "Floats"),
+			("Agent Author", "Someone"),
+			("Remove script", "rtar 2 3 4 kill targ"),
+			("Script 1", "inst\nnew: simp 2 3 4 \"lily\" 1 0 0\nendm\n")
+		], true)
+	])
+}
+
+fn unknown_blocks() -> Vec<u8> {
+	pray_file(&[
+		tag_block("DFAM", "Family Tree", &[("Generations", 3)], &[("Founder", "Alice"), ("Notes", "")], false),
+		write_block("ZZZZ", "mystery", &[0xde, 0xad, 0xbe, 0xef, 0, 1, 2, 3], true, 1).unwrap().to_vec(),
+		tag_block("DSAG", "Helper", &[("Agent Type", 0), ("Script Count", 0)], &[("Agent Description", "Helps")], false)
+	])
+}
+
+// a script file no tag uses, between the files of an agent that does
+fn loose_script() -> Vec<u8> {
+	pray_file(&[
+		file_block("crate.c16", &[1, 0, 0, 0, 1, 0].repeat(20), true),
+		tag_block("DSAG", "Crate", &[
+			("Agent Type", 0),
+			("Script Count", 1),
+			("Dependency Count", 1),
+			("Dependency Category 1", 2)
+		], &[
+			("Agent Description", "Holds things"),
+			("Dependency 1", "crate.c16"),
+			("Script 1", "inst\nnew: simp 2 4 1000 \"crate\" 1 0 0\nendm\n")
+		], false),
+		file_block("crate extras.cos", b"scrp 2 4 1000 1\nendm\n", false),
+		file_block("lid.wav", b"RIFF\x04\x00\x00\x00WAVE", false)
+	])
+}
+
+// keys that belong to the other game, and values the editor only writes in some cases
+fn misplaced_keys() -> Vec<u8> {
+	pray_file(&[
+		tag_block("AGNT", "Lamp", &[
+			("Agent Type", 0),
+			("Agent Sprite First Image", 0),
+			("Agent Bioenergy Value", 0),
+			("Dependency Count", 1),
+			("Dependency Category 1", 2),
+			("Script Count", 0)
+		], &[
+			("Web Label", "Lamps"),
+			("Agent Animation File", "lamp.c16"),
+			("Agent Animation Gallery", "lamp"),
+			("Agent Animation String", "0 1 2"),
+			("Web URL", "example.com/lamps"),
+			("Agent Description", "A lamp"),
+			("Dependency 1", "lamp.c16"),
+			("Remove script", "")
+		], false),
+		tag_block("DSAG", "Lamp", &[
+			("Agent Bioenergy Value", 25),
+			("Agent Type", 0),
+			("Dependency Count", 0),
+			("Script Count", 0)
+		], &[
+			("Agent Animation String", "3"),
+			("Web Label", ""),
+			("Web URL", ""),
+			("Remove script", "")
+		], false),
+		file_block("lamp.c16", &[1, 0, 0, 0, 1, 0, 4, 0, 4, 0].repeat(4), false)
+	])
+}
+
+fn corpus() -> Vec<(&'static str, Vec<u8>)> {
+	vec![
+		("AGNT", c3_agent()),
+		("DSAG", ds_agent()),
+		("EGGS", egg_agent()),
+		("DSGB", garden_box_agent()),
+		("unknown", unknown_blocks()),
+		("loose script", loose_script())
+	]
+}
+
+// compared through their serialized form, since that's everything the editor knows about them
+fn contents(bytes: &[u8]) -> serde_json::Value {
+	let (tags, dependencies) = split_blocks(decode(bytes).unwrap());
+	serde_json::json!({ "tags": tags, "dependencies": dependencies })
+}
+
+fn tag_values(bytes: &[u8]) -> Vec<(String, Tag)> {
+	let mut buffer = bytes::Bytes::copy_from_slice(&bytes[4..]);
+	let mut tags = Vec::new();
+	while !buffer.is_empty() {
+		let block_header = read_block_header(&mut buffer).unwrap();
+		let mut contents = read_block_contents(&mut buffer, &block_header).unwrap();
+		if matches!(block_header.id.as_str(), "AGNT" | "DSAG" | "DSGB" | "EGGS") {
+			tags.push((block_header.id, read_tag_block(&mut contents).unwrap()));
+		}
+	}
+	tags
+}
+
+fn block_ids(bytes: &[u8]) -> Vec<(String, String)> {
+	decode_layout_order(bytes).into_iter().map(|(id, name, _)| (id, name)).collect()
+}
+
+fn decode_layout_order(bytes: &[u8]) -> Vec<(String, String, Vec<u8>)> {
+	let mut buffer = bytes::Bytes::copy_from_slice(&bytes[4..]);
+	let mut blocks = Vec::new();
+	while !buffer.is_empty() {
+		let start = buffer.clone();
+		let block_header = read_block_header(&mut buffer).unwrap();
+		bytes::Buf::advance(&mut buffer, block_header.size_compressed);
+		let length = start.len() - buffer.len();
+		blocks.push((block_header.id, block_header.name, start[..length].to_vec()));
+	}
+	blocks
+}
+
+#[test]
+fn round_trip_keeps_contents() {
+	for (name, bytes) in corpus() {
+		let (tags, dependencies) = split_blocks(decode(&bytes).unwrap());
+		let encoded = encode(&tags, &dependencies).unwrap();
+		assert_eq!(contents(&bytes), contents(&encoded), "{} changed after saving", name);
+	}
+}
+
+#[test]
+fn round_trip_keeps_contents_with_any_compression() {
+	let options = CompressionOptions { compress_tags: true, compress_files: false, min_file_size: 0, level: 9 };
+	for (name, bytes) in corpus() {
+		let (tags, dependencies) = split_blocks(decode(&bytes).unwrap());
+		let encoded = encode_with_options(&tags, &dependencies, &options).unwrap();
+		assert_eq!(contents(&bytes), contents(&encoded), "{} changed after saving", name);
+	}
+}
+
+#[test]
+fn round_trip_keeps_compression_of_each_block() {
+	for (name, bytes) in corpus() {
+		let (tags, dependencies) = split_blocks(decode(&bytes).unwrap());
+		let encoded = encode(&tags, &dependencies).unwrap();
+		for (id, block_name, original) in decode_layout_order(&bytes) {
+			let is_compressed = |block: &[u8]| read_block_header(&mut bytes::Bytes::copy_from_slice(block)).unwrap().is_compressed;
+			if let Some((_, _, saved)) = decode_layout_order(&encoded).into_iter().find(|(i, n, _)| i == &id && n == &block_name) {
+				assert_eq!(is_compressed(&original), is_compressed(&saved), "{} {} in {}", id, block_name, name);
+			}
+		}
+	}
+}
+
+#[test]
+fn plain_saving_rewrites_blocks() {
+	// the corpus is laid out differently from how the editor writes agents, so this is what preserving the layout is for
+	for (name, bytes) in corpus() {
+		let (tags, dependencies) = split_blocks(decode(&bytes).unwrap());
+		assert_ne!(bytes, encode(&tags, &dependencies).unwrap().to_vec(), "{} was saved byte for byte", name);
+	}
+}
+
+#[test]
+fn preserved_layout_is_byte_identical() {
+	for (name, bytes) in corpus() {
+		let (blocks, layout) = decode_with_layout(&bytes).unwrap();
+		let (tags, dependencies) = split_blocks(blocks);
+		let encoded = encode_with_layout(&tags, &dependencies, &CompressionOptions::default(), &layout, &layout.order()).unwrap();
+		assert_eq!(bytes, encoded.to_vec(), "{} changed after saving", name);
+	}
+}
+
+#[test]
+fn preserved_layout_ignores_compression_options_for_untouched_blocks() {
+	let options = CompressionOptions { compress_tags: true, compress_files: true, min_file_size: 0, level: 9 };
+	for (name, bytes) in corpus() {
+		let (blocks, layout) = decode_with_layout(&bytes).unwrap();
+		let (tags, dependencies) = split_blocks(blocks);
+		let encoded = encode_with_layout(&tags, &dependencies, &options, &layout, &layout.order()).unwrap();
+		assert_eq!(bytes, encoded.to_vec(), "{} changed after saving", name);
+	}
+}
+
+#[test]
+fn preserved_layout_rewrites_changed_blocks_in_place() {
+	let bytes = c3_agent();
+	let (blocks, layout) = decode_with_layout(&bytes).unwrap();
+	let (mut tags, dependencies) = split_blocks(blocks);
+	if let Some(Block::Agent(agent)) = tags.first_mut() {
+		agent.bioenergy = 80;
+	}
+	let encoded = encode_with_layout(&tags, &dependencies, &CompressionOptions::default(), &layout, &layout.order()).unwrap();
+
+	assert_eq!(block_ids(&bytes), block_ids(&encoded));
+	let original_blocks = decode_layout_order(&bytes);
+	let saved_blocks = decode_layout_order(&encoded);
+	assert_ne!(original_blocks[0].2, saved_blocks[0].2);
+	assert_eq!(original_blocks[1..], saved_blocks[1..]);
+
+	let (tags, _) = split_blocks(decode(&encoded).unwrap());
+	match tags.first() {
+		Some(Block::Agent(agent)) => assert_eq!(agent.bioenergy, 80),
+		_ => panic!("agent tag is missing")
+	}
+}
+
+#[test]
+fn preserved_layout_rewrites_agent_when_its_script_changes() {
+	let bytes = c3_agent();
+	let (blocks, layout) = decode_with_layout(&bytes).unwrap();
+	let (tags, mut dependencies) = split_blocks(blocks);
+	if let Some(script) = dependencies.iter_mut().find(|d| d.filename() == "Hand Mirror 2.cos") {
+		script.data = b"scrp 2 21 1000 1\n\tsndc \"dong\"\nendm\n".to_vec();
+	}
+	let encoded = encode_with_layout(&tags, &dependencies, &CompressionOptions::default(), &layout, &layout.order()).unwrap();
+	assert_ne!(bytes, encoded.to_vec());
+	assert_eq!(block_ids(&bytes), block_ids(&encoded));
+	let (_, dependencies) = split_blocks(decode(&encoded).unwrap());
+	assert!(dependencies.iter().any(|d| d.data.ends_with(b"\"dong\"\nendm\n")));
+}
+
+#[test]
+fn preserved_layout_drops_removed_blocks_and_adds_new_ones_last() {
+	let bytes = egg_agent();
+	let (blocks, layout) = decode_with_layout(&bytes).unwrap();
+	let (tags, mut dependencies) = split_blocks(blocks);
+	let mut notes = dependencies.remove(dependencies.iter().position(|d| d.extension == "gno").unwrap());
+	notes.name = "norn.bondi.49".to_string();
+	dependencies.push(notes);
+	let encoded = encode_with_layout(&tags, &dependencies, &CompressionOptions::default(), &layout, &layout.order()).unwrap();
+
+	// the egg tag lists its dependencies, so it changes along with them
+	let original_blocks = decode_layout_order(&bytes);
+	let saved_blocks = decode_layout_order(&encoded);
+	assert_eq!(saved_blocks.len(), 3);
+	assert_eq!(saved_blocks[0].0, "EGGS");
+	assert_ne!(original_blocks[0], saved_blocks[0]);
+	assert_eq!(original_blocks[1], saved_blocks[1]);
+	assert_eq!(saved_blocks[2].1, "norn.bondi.49.gno");
+}
+
+#[test]
+fn saving_in_order_keeps_block_order() {
+	for (name, bytes) in corpus() {
+		let (blocks, layout) = decode_with_layout(&bytes).unwrap();
+		let (tags, dependencies) = split_blocks(blocks);
+		let encoded = encode_in_order(&tags, &dependencies, &CompressionOptions::default(), &layout.order()).unwrap();
+		assert_eq!(block_ids(&bytes), block_ids(&encoded), "{} was saved in a different order", name);
+		assert_eq!(contents(&bytes), contents(&encoded), "{} changed after saving", name);
+	}
+}
+
+#[test]
+fn block_order_lists_blocks_as_they_would_be_saved() {
+	for (name, bytes) in corpus() {
+		let (tags, dependencies) = split_blocks(decode(&bytes).unwrap());
+		let encoded = encode(&tags, &dependencies).unwrap();
+		assert_eq!(block_order(&tags, &dependencies, &[]), block_ids(&encoded), "{}", name);
+	}
+}
+
+#[test]
+fn unused_script_files_are_kept() {
+	let (tags, dependencies) = split_blocks(decode(&loose_script()).unwrap());
+	let encoded = encode(&tags, &dependencies).unwrap();
+	assert!(block_ids(&encoded).contains(&("FILE".to_string(), "crate extras.cos".to_string())));
+	// the agent's own script is still written into its tag rather than as a file
+	assert!(!block_ids(&encoded).iter().any(|(_, name)| name == "Crate.cos"));
+}
+
+#[test]
+fn reordered_blocks_are_saved_in_their_new_order() {
+	let bytes = loose_script();
+	let (blocks, layout) = decode_with_layout(&bytes).unwrap();
+	let (tags, dependencies) = split_blocks(blocks);
+	let mut order = layout.order();
+	order.reverse();
+
+	let encoded = encode_in_order(&tags, &dependencies, &CompressionOptions::default(), &order).unwrap();
+	assert_eq!(block_ids(&encoded), order);
+
+	// with the layout preserved, each block is still saved as it was, only somewhere else
+	let encoded = encode_with_layout(&tags, &dependencies, &CompressionOptions::default(), &layout, &order).unwrap();
+	let mut original_blocks = decode_layout_order(&bytes);
+	original_blocks.reverse();
+	assert_eq!(decode_layout_order(&encoded), original_blocks);
+}
+
+#[test]
+fn blocks_missing_from_the_order_are_saved_last() {
+	let bytes = loose_script();
+	let (tags, dependencies) = split_blocks(decode(&bytes).unwrap());
+	let order = vec![("FILE".to_string(), "lid.wav".to_string()), ("FILE".to_string(), "gone.wav".to_string())];
+	let encoded = encode_in_order(&tags, &dependencies, &CompressionOptions::default(), &order).unwrap();
+	let saved = block_ids(&encoded);
+	assert_eq!(saved[0], order[0]);
+	assert_eq!(saved[1..].to_vec(), block_order(&tags, &dependencies, &[]).into_iter().filter(|key| key != &order[0]).collect::<Vec<_>>());
+	assert_eq!(saved, block_order(&tags, &dependencies, &order));
+}
+
+// the editor may add keys a tag was missing, but every value it was read with should come back in its place
+#[test]
+fn tag_values_are_saved_as_they_were_read() {
+	for (name, bytes) in corpus().into_iter().chain([("misplaced keys", misplaced_keys())]) {
+		let (tags, dependencies) = split_blocks(decode(&bytes).unwrap());
+		let encoded = encode(&tags, &dependencies).unwrap();
+		for ((id, tag), (_, saved)) in tag_values(&bytes).into_iter().zip(tag_values(&encoded)) {
+			let saved_int_values: Vec<_> = saved.int_values.into_iter().filter(|(k, _)| tag.get_int(k).is_some()).collect();
+			let saved_str_values: Vec<_> = saved.str_values.into_iter().filter(|(k, _)| tag.get_str(k).is_some()).collect();
+			assert_eq!(tag.int_values, saved_int_values, "{} in {} changed after saving", id, name);
+			assert_eq!(tag.str_values, saved_str_values, "{} in {} changed after saving", id, name);
+		}
+	}
+}
+
+#[test]
+fn source_round_trip_keeps_contents() {
+	for (name, bytes) in corpus() {
+		let (tags, dependencies) = split_blocks(decode(&bytes).unwrap());
+		let (text, files) = pray_source::encode(&tags, &dependencies).unwrap();
+		assert!(pray_source::is_source_text(&text));
+
+		let root_path = std::env::temp_dir().join(format!("theist-source-{}", name));
+		std::fs::create_dir_all(&root_path).unwrap();
+		for file in files {
+			std::fs::write(root_path.join(file.filename()), &file.data).unwrap();
+		}
+		let mut blocks = pray_source::decode(&text, &root_path).unwrap();
+		std::fs::remove_dir_all(&root_path).unwrap();
+
+		// source files don't say how blocks are compressed
+		let mut original = decode(&bytes).unwrap();
+		for block in original.iter_mut().chain(blocks.iter_mut()) {
+			block.set_compression(Compression::Auto);
+		}
+		let (tags, dependencies) = split_blocks(original);
+		let (source_tags, source_dependencies) = split_blocks(blocks);
+		assert_eq!(serde_json::json!({ "tags": tags, "dependencies": dependencies }), serde_json::json!({ "tags": source_tags, "dependencies": source_dependencies }), "{} changed after exporting as source", name);
+	}
+}
+
+#[test]
+fn only_text_starting_with_a_language_is_source() {
+	assert!(pray_source::is_source_text("\"en-GB\"\n\ngroup DSAG \"Crate\"\n"));
+	assert!(pray_source::is_source_text("\u{feff}\n  \"de-DE\"\r\ninline FILE \"a.wav\" \"a.wav\"\n"));
+	assert!(!pray_source::is_source_text("Read me first\n\"en-GB\"\n"));
+	assert!(!pray_source::is_source_text("\"Hello\"\n"));
+	assert!(!pray_source::is_source_text(""));
+}
+
+#[test]
+fn compression_level_changes_how_small_blocks_get() {
+	// scripts repeat themselves a lot, but not so simply that every level finds the same matches
+	let data: Vec<u8> = (0..4000u32).flat_map(|i| format!("setv va{:02} {}\n", i % 37, (i * 7919) % 1009).into_bytes()).collect();
+	let sizes: Vec<usize> = (0..=9).map(|level| compress_block_contents(&data, level).unwrap().len()).collect();
+	for level in 0..=9 {
+		assert_eq!(decompress(&compress_block_contents(&data, level).unwrap()).unwrap(), data, "level {}", level);
+	}
+	assert!(sizes[0] > data.len());
+	assert!(sizes[9] < sizes[7] && sizes[7] < sizes[1], "{:?}", sizes);
+}
@@ -4,8 +4,13 @@ use std::{ fs, fmt };
 use tauri::{ AppHandle, Manager, State, Emitter };
 use tauri::menu::MenuItemKind;
 
+use theist::format::pray::CompressionOptions;
+
 pub struct ConfigState {
-	pub theme: Mutex<Theme>
+	pub theme: Mutex<Theme>,
+	pub block_save_on_errors: Mutex<bool>,
+	pub preserve_layout: Mutex<bool>,
+	pub compression: Mutex<CompressionOptions>
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -41,6 +46,12 @@ pub fn load_config_file(handle: &AppHandle) {
 					if let Some(value) = parts.get(1) {
 						if key.trim() == "theme"{
 							set_theme(handle, value.trim(), true);
+						} else if key.trim() == "block_save_on_errors" {
+							set_block_save_on_errors(handle, value.trim() == "true", true);
+						} else if key.trim() == "preserve_layout" {
+							set_preserve_layout(handle, value.trim() == "true", true);
+						} else {
+							load_compression_option(handle, key.trim(), value.trim());
 						}
 					}
 				}
@@ -54,9 +65,16 @@ pub fn save_config_file(handle: &AppHandle) {
 	if let Ok(config_dir) = handle.path().config_dir() {
 		let config_file_path = config_dir.join("theist.conf");
 		if let Ok(()) = fs::create_dir_all(config_dir) {
+			let compression = *config_state.compression.lock().unwrap();
 			fs::write(config_file_path, format!(
-				"theme: {}",
+				"theme: {}\nblock_save_on_errors: {}\npreserve_layout: {}\ncompress_tags: {}\ncompress_files: {}\nmin_file_size: {}\ncompression_level: {}",
 				config_state.theme.lock().unwrap(),
+				config_state.block_save_on_errors.lock().unwrap(),
+				config_state.preserve_layout.lock().unwrap(),
+				compression.compress_tags,
+				compression.compress_files,
+				compression.min_file_size,
+				compression.level,
 			)).unwrap();
 		}
 	}
@@ -90,3 +108,70 @@ pub fn set_theme(handle: &AppHandle, new_theme: &str, init: bool) {
 
 	if !init { save_config_file(handle); }
 }
+
+pub fn set_block_save_on_errors(handle: &AppHandle, new_value: bool, init: bool) {
+	if let Some(menu) = handle.menu() {
+		if let Some(MenuItemKind::Submenu(file_menu)) = menu.get("file") {
+			if let Some(MenuItemKind::Check(menu_item)) = file_menu.get("block_save_on_errors") {
+				menu_item.set_checked(new_value).unwrap();
+			};
+		}
+	}
+
+	let config_state: State<ConfigState> = handle.state();
+	*config_state.block_save_on_errors.lock().unwrap() = new_value;
+
+	if !init { save_config_file(handle); }
+}
+
+pub fn toggle_block_save_on_errors(handle: &AppHandle) {
+	let config_state: State<ConfigState> = handle.state();
+	let new_value = !*config_state.block_save_on_errors.lock().unwrap();
+	set_block_save_on_errors(handle, new_value, false);
+}
+
+pub fn set_preserve_layout(handle: &AppHandle, new_value: bool, init: bool) {
+	if let Some(menu) = handle.menu() {
+		if let Some(MenuItemKind::Submenu(file_menu)) = menu.get("file") {
+			if let Some(MenuItemKind::Check(menu_item)) = file_menu.get("preserve_layout") {
+				menu_item.set_checked(new_value).unwrap();
+			};
+		}
+	}
+
+	let config_state: State<ConfigState> = handle.state();
+	*config_state.preserve_layout.lock().unwrap() = new_value;
+
+	if !init { save_config_file(handle); }
+}
+
+pub fn toggle_preserve_layout(handle: &AppHandle) {
+	let config_state: State<ConfigState> = handle.state();
+	let new_value = !*config_state.preserve_layout.lock().unwrap();
+	set_preserve_layout(handle, new_value, false);
+}
+
+fn load_compression_option(handle: &AppHandle, key: &str, value: &str) {
+	let config_state: State<ConfigState> = handle.state();
+	let mut compression = config_state.compression.lock().unwrap();
+	match key {
+		"compress_tags" => compression.compress_tags = value == "true",
+		"compress_files" => compression.compress_files = value == "true",
+		"min_file_size" => compression.min_file_size = value.parse().unwrap_or(compression.min_file_size),
+		"compression_level" => compression.level = value.parse::<u8>().unwrap_or(compression.level).min(9),
+		_ => {}
+	}
+}
+
+pub fn show_compression_dialog(handle: &AppHandle) {
+	let config_state: State<ConfigState> = handle.state();
+	let compression = *config_state.compression.lock().unwrap();
+	handle.emit("show_compression_dialog", compression).unwrap();
+}
+
+#[tauri::command]
+pub fn set_compression_options(handle: AppHandle, options: CompressionOptions) {
+	let config_state: State<ConfigState> = handle.state();
+	*config_state.compression.lock().unwrap() = CompressionOptions { level: options.level.min(9), ..options };
+	save_config_file(&handle);
+}
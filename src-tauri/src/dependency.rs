@@ -12,20 +12,86 @@ use tauri::async_runtime::spawn;
 use rfd::{ MessageDialog, MessageButtons, MessageDialogResult };
 
 use crate::error_dialog;
-use crate::file::{ FileState, modify_file, create_file_dialog };
-use crate::format::pray::Block;
-use crate::format::file_block::File;
-use crate::sprite::{ blk, c16, s16, image_error, export_sprite };
+use crate::file::{ FileState, ImageCache, modify_file, create_file_dialog };
+use crate::format::pray::{ Block, Compression };
+use crate::format::file_block::{ File, SUPPORTED_EXTENSIONS, sort_dependencies, file_path_in };
+use crate::format::cp1252;
+use crate::format::caos::{ self, Script, ScriptKind, ReferenceKind };
+use crate::format::catalogue::{ self, Catalogue };
+use crate::format::genome::{ self, Gene };
+use crate::format::gene_notes::{ self, GeneNote };
+use crate::format::attachment::{ self, AttachmentPoints };
+use crate::format::wav::{ self, WavInfo };
+use crate::format::mng;
+use crate::format::discovery;
+use crate::sprite::{ blk, c16, s16, image_error, export_sprite, export_sprite_sheet, export_animation, export_background, import_sprite, sort_frame_paths, sprite_name_from_paths, EncodeOptions, PixelFormat, Dithering, SheetLayout };
+use crate::sprite::animation::FRAME_DELAY_MS;
+
+#[derive(Clone, Copy, serde::Deserialize)]
+pub enum SpriteExportFormat {
+	Frames,
+	HorizontalSheet,
+	GridSheet,
+	Gif,
+	Apng
+}
 
 #[derive(Clone, serde::Serialize)]
 struct DependencyInfo {
 	index: usize,
 	filename: String,
 	text: String,
-	framecount: usize
+	framecount: usize,
+	scripts: Vec<ScriptInfo>,
+	classifiers: Vec<String>,
+	catalogue: Option<Catalogue>,
+	genes: Vec<GeneInfo>,
+	attachment: Option<AttachmentInfo>,
+	sound: Option<WavInfo>,
+	music: Option<MusicInfo>,
+	compression: Compression,
+	errors: Vec<String>
+}
+
+#[derive(Clone, serde::Serialize)]
+struct MusicInfo {
+	tracks: Vec<String>,
+	samples: Vec<SampleInfo>
 }
 
-static SUPPORTED_EXTENSIONS: [&str; 10] = ["cos", "wav", "mng", "c16", "s16", "blk", "gen", "gno", "att", "catalogue"];
+#[derive(Clone, serde::Serialize)]
+struct SampleInfo {
+	name: String,
+	sound: Option<WavInfo>
+}
+
+#[derive(Clone, serde::Serialize)]
+struct AttachmentInfo {
+	sprite: String, // the body part sprite the points belong to, if the agent has it
+	points: AttachmentPoints
+}
+
+#[derive(Clone, serde::Serialize)]
+struct GeneInfo {
+	title: String,
+	id: u8,
+	generation: u8,
+	life_stage: String,
+	flags: Vec<&'static str>,
+	mutability: u8,
+	variant: u8,
+	fields: Vec<(String, String)>,
+	caption: String,
+	note: String
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ScriptInfo {
+	title: String,
+	start_line: usize,
+	end_line: usize,
+	text: String
+}
 
 
 #[tauri::command]
@@ -58,7 +124,8 @@ pub fn add_dependency_from_path(handle: &AppHandle, file_path: PathBuf) -> Resul
 				name: name.to_string(),
 				extension: extension.to_string(),
 				data: bytes,
-				is_checked: true
+				is_checked: true,
+				compression: Compression::Auto
 			};
 
 			dependencies.push(new_dependency);
@@ -87,6 +154,167 @@ pub fn add_dependency_from_path(handle: &AppHandle, file_path: PathBuf) -> Resul
 	}
 }
 
+#[tauri::command]
+pub fn create_sprite(handle: AppHandle, extension: String, pixel_format: PixelFormat, dithering: Dithering) {
+	let file_dialog = create_file_dialog(&handle)
+		.add_filter("PNG Images", &["png", "PNG"]);
+	let file_handles = match extension.as_str() {
+		"blk" => file_dialog.pick_file().map(|file_handle| vec![file_handle]),
+		_ => file_dialog.pick_files()
+	};
+	if let Some(mut frame_paths) = file_handles {
+		sort_frame_paths(&mut frame_paths);
+		let options = EncodeOptions { pixel_format, dithering };
+		if let Err(why) = create_sprite_from_paths(&handle, &extension, &frame_paths, &options) {
+			error_dialog(why.to_string());
+		}
+	}
+}
+
+fn create_sprite_from_paths(handle: &AppHandle, extension: &str, frame_paths: &[PathBuf], options: &EncodeOptions) -> Result<(), Box<dyn Error>> {
+	let name = sprite_name_from_paths(frame_paths);
+	let new_sprite = import_sprite(&name, extension, frame_paths, options)?;
+	let dependency_name = new_sprite.filename();
+
+	let file_state: State<FileState> = handle.state();
+	let existing_index = file_state.dependencies.lock().unwrap().iter().position(|d| d.filename() == dependency_name);
+
+	if let Some(existing_index) = existing_index {
+		let confirm_replace = MessageDialog::new()
+			.set_title("Replace Sprite")
+			.set_description(format!("Replace \"{}\" in agent with the new frames?", dependency_name))
+			.set_buttons(MessageButtons::YesNo)
+			.show();
+		if let MessageDialogResult::Yes = confirm_replace {
+			modify_file(handle, true);
+			let mut dependencies = file_state.dependencies.lock().unwrap();
+			if let Some(dependency) = dependencies.get_mut(existing_index) {
+				dependency.data = new_sprite.data;
+			}
+			file_state.image_cache.lock().unwrap().remove(&dependency_name);
+			handle.emit("update_dependency_list", dependencies.clone()).unwrap();
+			handle.emit("show_notification", "Sprite replaced").unwrap();
+		}
+		return Ok(());
+	}
+
+	modify_file(handle, true);
+	let mut dependencies = file_state.dependencies.lock().unwrap();
+	dependencies.push(new_sprite);
+	sort_dependencies(&mut dependencies);
+
+	let mut tags = file_state.tags.lock().unwrap();
+	let selected_tag = *file_state.selected_tag.lock().unwrap();
+	if let Some(selected_tag_index) = selected_tag {
+		if let Some(tag) = tags.get_mut(selected_tag_index) {
+			match tag {
+				Block::Agent(ref mut t) => { t.dependencies.push(dependency_name); }
+				Block::Egg(ref mut t) => { t.dependencies.push(dependency_name); }
+				Block::GardenBox(ref mut t) => { t.dependencies.push(dependency_name); }
+				_ => {}
+			}
+			check_dependencies_for_tag(tag, &mut dependencies);
+		}
+	}
+
+	handle.emit("update_dependency_list", dependencies.clone()).unwrap();
+	handle.emit("show_notification", "Sprite created").unwrap();
+	Ok(())
+}
+
+#[tauri::command]
+pub fn discover_dependencies(handle: AppHandle, file_state: State<FileState>) {
+	let selected_tag = *file_state.selected_tag.lock().unwrap();
+	let tag_dependencies = match selected_tag.and_then(|i| file_state.tags.lock().unwrap().get(i).cloned()) {
+		Some(Block::Agent(t)) => Some(t.dependencies),
+		Some(Block::Egg(t)) => Some(t.dependencies),
+		Some(Block::GardenBox(t)) => Some(t.dependencies),
+		_ => None
+	};
+
+	// only scan the selected tag's scripts, so files used by other tags aren't pulled in
+	let report = {
+		let dependencies = file_state.dependencies.lock().unwrap();
+		let scripts: Vec<&File> = dependencies.iter()
+			.filter(|d| d.extension == "cos")
+			.filter(|d| tag_dependencies.as_ref().map_or(true, |t| t.contains(&d.filename())))
+			.collect();
+		if scripts.is_empty() {
+			MessageDialog::new()
+				.set_title("No Scripts Found")
+				.set_description("There are no COS files to scan for this tag.")
+				.show();
+			return;
+		}
+		discovery::discover_dependencies(&scripts, &dependencies)
+	};
+
+	let unchecked_files: Vec<String> = match &tag_dependencies {
+		Some(tag_dependencies) => report.found.iter().filter(|f| !tag_dependencies.contains(f)).cloned().collect(),
+		None => Vec::new()
+	};
+
+	let mut description = String::new();
+	for why in &report.errors {
+		description += &format!("Unable to scan {}\n", why);
+	}
+	if !report.missing.is_empty() {
+		description += "Missing from agent:\n";
+		for missing in &report.missing {
+			let kind = match missing.reference.kind {
+				ReferenceKind::Sprite => "sprite",
+				ReferenceKind::Sound => "sound",
+				ReferenceKind::MusicTrack => "music track",
+				ReferenceKind::CatalogueTag => "catalogue tag",
+				ReferenceKind::Genome => "genome"
+			};
+			description += &format!("    {} \"{}\" ({}, line {})\n", kind, missing.reference.name, missing.script_file, missing.reference.line);
+		}
+	}
+
+	if unchecked_files.is_empty() {
+		if description.is_empty() {
+			description = "All files used by the scripts are in the agent and checked for this tag.".to_string();
+		}
+		MessageDialog::new()
+			.set_title("Script Dependencies")
+			.set_description(description.trim_end())
+			.show();
+		return;
+	}
+
+	if !description.is_empty() {
+		description += "\n";
+	}
+	description += "In agent but not checked for this tag:\n";
+	for file_name in &unchecked_files {
+		description += &format!("    {}\n", file_name);
+	}
+	description += "\nCheck them now?";
+
+	let confirm_check = MessageDialog::new()
+		.set_title("Script Dependencies")
+		.set_description(description)
+		.set_buttons(MessageButtons::YesNo)
+		.show();
+
+	if let MessageDialogResult::Yes = confirm_check {
+		modify_file(&handle, true);
+		let mut dependencies = file_state.dependencies.lock().unwrap();
+		let mut tags = file_state.tags.lock().unwrap();
+		if let Some(tag) = selected_tag.and_then(|i| tags.get_mut(i)) {
+			match tag {
+				Block::Agent(ref mut t) => { t.dependencies.extend(unchecked_files); }
+				Block::Egg(ref mut t) => { t.dependencies.extend(unchecked_files); }
+				Block::GardenBox(ref mut t) => { t.dependencies.extend(unchecked_files); }
+				_ => {}
+			}
+			let checked_dependencies = check_dependencies_for_tag(tag, &mut dependencies);
+			handle.emit("update_checked_dependencies", &checked_dependencies).unwrap();
+		}
+	}
+}
+
 #[tauri::command]
 pub fn extract_dependency(handle: AppHandle, selected_dependencies: Vec<u32>) {
 	let file_handle = create_file_dialog(&handle)
@@ -105,8 +333,13 @@ pub fn extract_dependency(handle: AppHandle, selected_dependencies: Vec<u32>) {
 		for (i, dependency) in dependencies.iter().enumerate() {
 			if selected_dependencies.contains(&(i as u32)) {
 				let dependency_name = dependency.filename();
-				let mut dependency_path = file_path.clone();
-				dependency_path.push(dependency_name.clone());
+				let dependency_path = match file_path_in(&file_path, &dependency_name) {
+					Ok(dependency_path) => dependency_path,
+					Err(why) => {
+						error_dialog(why.to_string());
+						return;
+					}
+				};
 				if let Ok(path_exists) = dependency_path.try_exists() {
 					if path_exists {
 						num_files_to_overwrite += 1;
@@ -148,12 +381,17 @@ pub fn extract_dependency(handle: AppHandle, selected_dependencies: Vec<u32>) {
 }
 
 #[tauri::command]
-pub fn export_dependency(handle: AppHandle, file_state: State<FileState>, index: usize, selected_frames: Vec<usize>) {
+pub fn export_dependency(handle: AppHandle, file_state: State<FileState>, index: usize, selected_frames: Vec<usize>, sprite_format: Option<SpriteExportFormat>, frame_delay: Option<u32>) {
+	let sprite_format = sprite_format.unwrap_or(SpriteExportFormat::Frames);
+	let frame_delay = frame_delay.unwrap_or(FRAME_DELAY_MS);
 	let dependencies = file_state.dependencies.lock().unwrap();
 	let file_dialog_opt = match dependencies.get(index) {
 		Some(dependency) => {
 			let new_file_name = match dependency.extension.to_lowercase().as_str() {
-				"c16" | "s16" | "blk" => format!("{}.png", dependency.name),
+				"c16" | "s16" | "blk" => match sprite_format {
+					SpriteExportFormat::Gif => format!("{}.gif", dependency.name),
+					_ => format!("{}.png", dependency.name)
+				},
 				_ => format!("{}_{}.txt", dependency.name, dependency.extension)
 			};
 			Some(create_file_dialog(&handle).set_file_name(new_file_name))
@@ -169,7 +407,17 @@ pub fn export_dependency(handle: AppHandle, file_state: State<FileState>, index:
 				if let Some(dependency) = dependencies.get(index) {
 					let file_path = file_handle.as_path();
 					match dependency.extension.to_lowercase().as_str() {
-						"c16" | "s16" | "blk" => export_sprite(dependency, &file_handle, &selected_frames),
+						"c16" | "s16" | "blk" => {
+							let result = match sprite_format {
+								SpriteExportFormat::Frames => export_sprite(dependency, &file_handle, &selected_frames),
+								SpriteExportFormat::HorizontalSheet => export_sprite_sheet(dependency, &file_handle, &selected_frames, SheetLayout::Horizontal),
+								SpriteExportFormat::GridSheet => export_sprite_sheet(dependency, &file_handle, &selected_frames, SheetLayout::Grid),
+								SpriteExportFormat::Gif | SpriteExportFormat::Apng => export_animation(dependency, &file_handle, &selected_frames, frame_delay)
+							};
+							if let Err(why) = result {
+								error_dialog(why.to_string());
+							}
+						}
 						_ => {
 							if let Err(why) = fs::write(file_path, &dependency.data) {
 								error_dialog(why.to_string());
@@ -182,6 +430,52 @@ pub fn export_dependency(handle: AppHandle, file_state: State<FileState>, index:
 	}
 }
 
+#[tauri::command]
+pub fn export_full_background(handle: AppHandle, file_state: State<FileState>, index: usize) {
+	let dependencies = file_state.dependencies.lock().unwrap();
+	let file_dialog_opt = dependencies.get(index)
+		.filter(|dependency| dependency.extension.to_lowercase() == "blk")
+		.map(|dependency| create_file_dialog(&handle).set_file_name(format!("{}.png", dependency.name)));
+	if let Some(file_dialog) = file_dialog_opt {
+		let file_handle = file_dialog.save_file();
+		if let Some(file_handle) = file_handle {
+			spawn(async move {
+				let file_state: State<FileState> = handle.state();
+				let dependencies = file_state.dependencies.lock().unwrap();
+				if let Some(dependency) = dependencies.get(index) {
+					match export_background(dependency, &file_handle) {
+						Ok(()) => handle.emit("show_notification", "Background exported").unwrap(),
+						Err(why) => error_dialog(why.to_string())
+					}
+				}
+			});
+		}
+	}
+}
+
+#[tauri::command]
+pub fn export_music_sample(handle: AppHandle, file_state: State<FileState>, index: usize, sample: usize) {
+	let dependencies = file_state.dependencies.lock().unwrap();
+	let music = match dependencies.get(index).map(|dependency| mng::decode(&dependency.data)) {
+		Some(Ok(music)) => music,
+		Some(Err(why)) => return error_dialog(why.to_string()),
+		None => return
+	};
+	let sample_name = music.wave_names().get(sample).cloned().unwrap_or_else(|| format!("Sample {}", sample + 1));
+	if let Some(data) = music.sample_wav(sample) {
+		let file_handle = create_file_dialog(&handle)
+			.add_filter("Sounds", &["wav"])
+			.set_file_name(format!("{}.wav", sample_name))
+			.save_file();
+		if let Some(file_handle) = file_handle {
+			match fs::write(file_handle.as_path(), data) {
+				Ok(()) => handle.emit("show_notification", "Sample exported").unwrap(),
+				Err(why) => error_dialog(why.to_string())
+			}
+		}
+	}
+}
+
 #[tauri::command]
 pub fn reload_dependency(handle: AppHandle, file_state: State<FileState>, selected_dependencies: Vec<usize>) {
 	let do_reload = |handle: AppHandle| -> Result<(), Box<dyn Error>> {
@@ -349,56 +643,219 @@ pub fn select_dependency(handle: AppHandle, file_state: State<FileState>, select
 	let dependencies = file_state.dependencies.lock().unwrap();
 	let mut image_cache = file_state.image_cache.lock().unwrap();
 	if let Some(dependency) = dependencies.get(selected_dependency) {
-		let no_contents = DependencyInfo {
+		let mut info = DependencyInfo {
 			index: selected_dependency,
 			filename: dependency.filename(),
-			text: "".to_string(),
-			framecount: 0
+			text: String::new(),
+			framecount: 0,
+			scripts: Vec::new(),
+			classifiers: Vec::new(),
+			catalogue: None,
+			genes: Vec::new(),
+			attachment: None,
+			sound: None,
+			music: None,
+			compression: dependency.compression,
+			errors: Vec::new()
 		};
-		let info = match dependency.extension.as_str() {
-			"cos" | "catalogue" => DependencyInfo {
-				index: selected_dependency,
-				filename: dependency.filename(),
-				text: String::from_utf8_lossy(&dependency.data).to_string(),
-				framecount: 0
-			},
-			"c16" | "s16" | "blk" => {
-				match image_cache.get(&dependency.filename()) {
-					Some(cache) => DependencyInfo {
-						index: selected_dependency,
-						filename: dependency.filename(),
-						text: String::new(),
-						framecount: cache.len()
-					},
-					None => {
-						let frame_result = match dependency.extension.as_str() {
-							"blk" => blk::decode(&dependency.data),
-							"c16" => c16::decode(&dependency.data),
-							"s16" => s16::decode(&dependency.data),
-							_ => Err(image_error()),
+		match dependency.extension.as_str() {
+			"cos" => {
+				let text = cp1252::decode(&dependency.data);
+				match caos::parse(&text) {
+					Ok(caos_file) => {
+						info.scripts = caos_file.scripts().map(script_info).collect();
+						info.classifiers = caos_file.classifiers().iter().map(|classifier| {
+							let events: Vec<String> = caos_file.events(classifier).iter().map(|event| match caos::event_name(*event) {
+								Some(name) => format!("{} ({})", event, name),
+								None => event.to_string()
+							}).collect();
+							format!("{} {} {}: {}", classifier.family, classifier.genus, classifier.species, events.join(", "))
+						}).collect();
+					}
+					Err(why) => {
+						info.errors.push(why.to_string());
+						info.text = text;
+					}
+				}
+			}
+			"catalogue" => {
+				let text = cp1252::decode(&dependency.data);
+				match catalogue::parse(&text) {
+					Ok(catalogue) => {
+						info.errors = catalogue.check();
+						info.catalogue = Some(catalogue);
+					}
+					Err(why) => {
+						info.errors.push(why.to_string());
+						info.text = text;
+					}
+				}
+			}
+			"gen" => {
+				match genome::decode(&dependency.data) {
+					Ok(genome) => {
+						// notes live in a .gno file with the same name as the genome
+						let notes = match dependencies.iter().find(|d| d.extension == "gno" && d.name.eq_ignore_ascii_case(&dependency.name)) {
+							Some(notes_file) => gene_notes::decode(&notes_file.data).unwrap_or_else(|why| {
+								info.errors.push(format!("Unable to read notes from \"{}\": {}", notes_file.filename(), why));
+								Vec::new()
+							}),
+							None => Vec::new()
 						};
-						match frame_result {
-							Ok(frames) => {
-								let framecount = frames.len();
-								image_cache.insert(dependency.filename(), frames);
-								DependencyInfo {
-									index: selected_dependency,
-									filename: dependency.filename(),
-									text: String::new(),
-									framecount
+						info.genes = genome.genes.iter()
+							.zip(gene_notes::match_notes(&genome, &notes))
+							.map(|(gene, note)| gene_info(gene, note))
+							.collect();
+					}
+					Err(why) => {
+						info.errors.push(why.to_string());
+					}
+				}
+			}
+			"gno" => {
+				match gene_notes::decode(&dependency.data) {
+					Ok(notes) => {
+						info.text = notes.iter()
+							.map(|note| format!("{} {} {}: {}\n{}", note.gene_type, note.subtype, note.id, note.caption, note.plain_text()))
+							.collect::<Vec<String>>()
+							.join("\n\n");
+					}
+					Err(why) => {
+						info.errors.push(why.to_string());
+					}
+				}
+			}
+			"att" => {
+				match attachment::parse(&cp1252::decode(&dependency.data)) {
+					Ok(points) => {
+						let sprite = dependencies.iter().find(|d| d.extension == "c16" && d.name.eq_ignore_ascii_case(&dependency.name));
+						let mut attachment_info = AttachmentInfo { sprite: String::new(), points };
+						match sprite {
+							Some(sprite) => {
+								info.framecount = cache_sprite_frames(sprite, &mut image_cache).unwrap_or(0);
+								if let Some(frames) = image_cache.get(&sprite.filename()) {
+									let frame_sizes: Vec<(u32, u32)> = frames.iter().map(|frame| frame.dimensions()).collect();
+									info.errors = attachment::check_frames(&attachment_info.points, &sprite.filename(), &frame_sizes);
 								}
-							},
-							Err(_) => no_contents
+								attachment_info.sprite = sprite.filename();
+							}
+							None => {
+								info.errors.push(format!("No body part sprite named \"{}.c16\" to check the points against", dependency.name));
+							}
 						}
+						info.attachment = Some(attachment_info);
+					}
+					Err(why) => {
+						info.errors.push(why.to_string());
+						info.text = cp1252::decode(&dependency.data);
 					}
 				}
-			},
-			_ => no_contents
-		};
+			}
+			"wav" => {
+				match wav::read_info(&dependency.data) {
+					Ok(sound) => {
+						info.errors = sound.warnings();
+						info.sound = Some(sound);
+					}
+					Err(why) => {
+						info.errors.push(why.to_string());
+					}
+				}
+			}
+			"mng" => {
+				match mng::decode(&dependency.data) {
+					Ok(music) => {
+						let wave_names = music.wave_names();
+						let samples = (0..music.samples.len()).map(|i| SampleInfo {
+							name: wave_names.get(i).cloned().unwrap_or_else(|| format!("Sample {}", i + 1)),
+							sound: music.sample_wav(i).and_then(|data| wav::read_info(&data).ok())
+						}).collect();
+						info.music = Some(MusicInfo { tracks: music.track_names(), samples });
+						info.text = music.script;
+					}
+					Err(why) => {
+						info.errors.push(why.to_string());
+					}
+				}
+			}
+			"c16" | "s16" | "blk" => {
+				info.framecount = cache_sprite_frames(dependency, &mut image_cache).unwrap_or(0);
+			}
+			_ => {}
+		}
 		handle.emit("update_dependency_info", info).unwrap();
 	}
 }
 
+#[tauri::command]
+pub fn update_catalogue(handle: AppHandle, file_state: State<FileState>, index: usize, catalogue: Catalogue) {
+	modify_file(&handle, true);
+	if let Some(dependency) = file_state.dependencies.lock().unwrap().get_mut(index) {
+		dependency.data = cp1252::encode(&catalogue::encode(&catalogue));
+	}
+}
+
+#[tauri::command]
+pub fn update_dependency_compression(handle: AppHandle, file_state: State<FileState>, index: usize, compression: Compression) {
+	modify_file(&handle, true);
+	if let Some(dependency) = file_state.dependencies.lock().unwrap().get_mut(index) {
+		dependency.compression = compression;
+	}
+}
+
+fn gene_info(gene: &Gene, note: Option<&GeneNote>) -> GeneInfo {
+	GeneInfo {
+		title: gene.kind_name(),
+		id: gene.id,
+		generation: gene.generation,
+		life_stage: gene.life_stage(),
+		flags: gene.flag_names(),
+		mutability: gene.mutability,
+		variant: gene.variant,
+		fields: gene.fields(),
+		caption: note.map(|note| note.caption.clone()).unwrap_or_default(),
+		note: note.map(|note| note.plain_text()).unwrap_or_default()
+	}
+}
+
+fn script_info(script: &Script) -> ScriptInfo {
+	let title = match script.kind {
+		ScriptKind::Install => "Install Script".to_string(),
+		ScriptKind::Remove => "Remove Script".to_string(),
+		ScriptKind::Event { classifier, event } => {
+			let event_name = caos::event_name(event).map(|name| format!(" ({})", name)).unwrap_or_default();
+			format!("Event Script {} {} {} {}{}", classifier.family, classifier.genus, classifier.species, event, event_name)
+		}
+	};
+	ScriptInfo {
+		title,
+		start_line: script.start_line,
+		end_line: script.end_line,
+		text: script.text.clone()
+	}
+}
+
+// decodes a sprite into the image cache if it isn't there already, returning its frame count
+pub fn cache_sprite_frames(dependency: &File, image_cache: &mut ImageCache) -> Option<usize> {
+	if let Some(cache) = image_cache.get(&dependency.filename()) {
+		return Some(cache.len());
+	}
+	let frame_result = match dependency.extension.as_str() {
+		"blk" => blk::decode(&dependency.data),
+		"c16" => c16::decode(&dependency.data),
+		"s16" => s16::decode(&dependency.data),
+		_ => Err(image_error()),
+	};
+	match frame_result {
+		Ok(frames) => {
+			let framecount = frames.len();
+			image_cache.insert(dependency.filename(), frames);
+			Some(framecount)
+		},
+		Err(_) => None
+	}
+}
+
 #[tauri::command]
 pub fn deselect_dependency(handle: AppHandle, file_state: State<FileState>) {
 	if let Some(selected_tag) = *file_state.selected_tag.lock().unwrap() {
@@ -434,19 +891,3 @@ pub fn check_dependencies_for_tag(tag: &Block, dependencies: &mut [File]) -> Vec
 
 	checked_dependencies
 }
-
-pub fn sort_dependencies(dependencies: &mut [File]) {
-	dependencies.sort_by_key(|d| (match d.extension.as_str() {
-		"cos" => 0,
-		"gen" => 1,
-		"gno" => 2,
-		"catalogue" => 3,
-		"blk" => 4,
-		"c16" => 5,
-		"s16" => 6,
-		"mng" => 7,
-		"wav" => 8,
-		"att" => 9,
-		_ => 10
-	}, d.name.clone()));
-}
@@ -15,14 +15,18 @@ use image::RgbaImage;
 
 use crate::error_dialog;
 use crate::update_title;
-use crate::format::pray::{ Block, encode, decode };
-use crate::format::file_block::File;
+use crate::config::ConfigState;
+use crate::format::pray::{ Block, Layout, block_order, check_encoding, encode_in_order, encode_with_layout, decode_with_layout };
+use crate::format::validation::{ validate, has_errors };
+use crate::format::pray_source;
+use crate::format::cob;
+use crate::format::cp1252;
+use crate::format::file_block::{ File, sort_dependencies, file_path_in };
 use crate::history::{
 	reset_history,
 	add_history_state
 };
 use crate::dependency::{
-	sort_dependencies,
 	check_dependencies_for_tag,
 	add_dependency_from_path
 };
@@ -33,6 +37,8 @@ pub struct FileState {
 	pub dependencies: Mutex<Vec<File>>,
 	pub tags: Mutex<Vec<Block>>,
 	pub selected_tag: Mutex<Option<usize>>,
+	pub layout: Mutex<Option<Layout>>, // how the opened file was laid out, for saving unchanged blocks as they were
+	pub block_order: Mutex<Vec<(String, String)>>, // the id and name of each block in the order they're saved in
 	pub image_cache: Mutex<ImageCache>
 }
 
@@ -52,6 +58,9 @@ impl ImageCache {
 	pub fn insert(&mut self, key: String, value: Vec<RgbaImage>) -> Option<Vec<RgbaImage>> {
 		self.0.insert(key, value)
 	}
+	pub fn remove(&mut self, key: &str) -> Option<Vec<RgbaImage>> {
+		self.0.remove(key)
+	}
 }
 
 pub fn check_file_modified(handle: AppHandle, path: PathBuf, callback: FileModifiedCallback) {
@@ -109,6 +118,8 @@ pub fn new_file(handle: AppHandle) {
 		*file_state.dependencies.lock().unwrap() = Vec::new();
 		*file_state.tags.lock().unwrap() = Vec::new();
 		*file_state.selected_tag.lock().unwrap() = None;
+		*file_state.layout.lock().unwrap() = None;
+		*file_state.block_order.lock().unwrap() = Vec::new();
 		reset_history(&handle);
 		reset_file_modified(&handle);
 		handle.emit("update_tag_list", (0, Vec::<String>::new())).unwrap();
@@ -127,6 +138,7 @@ pub fn open_file_dialog(handle: &AppHandle) {
 	let handle = handle.clone();
 	let file_handle = create_file_dialog(&handle)
 		.add_filter("Agents", &["agent", "agents"])
+		.add_filter("Exported Creatures", &["creature"])
 		.pick_file();
 	if let Some(file_handle) = file_handle {
 		match open_file_from_path(&handle, &file_handle.as_path().to_path_buf()) {
@@ -140,10 +152,82 @@ pub fn open_file_from_path(handle: &AppHandle, file_path: &PathBuf) -> Result<()
 	handle.emit("show_spinner", ()).unwrap();
 
 	let bytes = fs::read(file_path)?;
-	let blocks = decode(&bytes)?;
+	let (blocks, layout) = decode_with_layout(&bytes)?;
+
+	load_blocks(handle, Some(file_path.clone()), blocks, Some(layout));
+
+	handle.emit("hide_spinner", ()).unwrap();
+
+	Ok(())
+}
+
+#[tauri::command]
+pub fn import_source_file(handle: AppHandle) {
+	check_file_modified(handle, PathBuf::new(), FileModifiedCallback { func: |handle, _| {
+		let file_handle = create_file_dialog(&handle)
+			.add_filter("PRAYsource", &["txt"])
+			.pick_file();
+		if let Some(file_handle) = file_handle {
+			if let Err(why) = import_source_file_from_path(&handle, &file_handle.as_path().to_path_buf()) {
+				handle.emit("hide_spinner", ()).unwrap();
+				error_dialog(why.to_string());
+			}
+		}
+	}});
+}
+
+pub fn import_source_file_from_path(handle: &AppHandle, file_path: &Path) -> Result<(), Box<dyn Error>> {
+	handle.emit("show_spinner", ()).unwrap();
+
+	let text = pray_source::decode_text(&fs::read(file_path)?);
+	let root_path = file_path.parent().unwrap_or(Path::new(""));
+	let blocks = pray_source::decode(&text, root_path)?;
+
+	// the agent hasn't been saved anywhere yet, so it shouldn't overwrite the source file
+	load_blocks(handle, None, blocks, None);
+	modify_file(handle, false);
+
+	handle.emit("hide_spinner", ()).unwrap();
+
+	Ok(())
+}
+
+#[tauri::command]
+pub fn import_cob_file(handle: AppHandle) {
+	check_file_modified(handle, PathBuf::new(), FileModifiedCallback { func: |handle, _| {
+		let file_handle = create_file_dialog(&handle)
+			.add_filter("COB", &["cob"])
+			.pick_file();
+		if let Some(file_handle) = file_handle {
+			if let Err(why) = import_cob_file_from_path(&handle, &file_handle.as_path().to_path_buf()) {
+				handle.emit("hide_spinner", ()).unwrap();
+				error_dialog(why.to_string());
+			}
+		}
+	}});
+}
+
+pub fn import_cob_file_from_path(handle: &AppHandle, file_path: &Path) -> Result<(), Box<dyn Error>> {
+	handle.emit("show_spinner", ()).unwrap();
+
+	let cob = cob::decode(&fs::read(file_path)?)?;
+	let blocks = cob::convert_to_agent(&cob)?;
+
+	// like a PRAYsource import, the converted agent is new and shouldn't overwrite the COB
+	load_blocks(handle, None, blocks, None);
+	modify_file(handle, false);
 
+	handle.emit("hide_spinner", ()).unwrap();
+
+	Ok(())
+}
+
+fn load_blocks(handle: &AppHandle, file_path: Option<PathBuf>, blocks: Vec<Block>, layout: Option<Layout>) {
 	let file_state: State<FileState> = handle.state();
-	*file_state.path.lock().unwrap() = Some(file_path.clone());
+	*file_state.path.lock().unwrap() = file_path;
+	// blocks are saved in the order they were read, and imported ones in the order the editor would write them
+	*file_state.block_order.lock().unwrap() = layout.as_ref().map(|layout| layout.order()).unwrap_or_default();
+	*file_state.layout.lock().unwrap() = layout;
 
 	reset_history(handle);
 	reset_file_modified(handle);
@@ -178,10 +262,6 @@ pub fn open_file_from_path(handle: &AppHandle, file_path: &PathBuf) -> Result<()
 
 	*file_state.dependencies.lock().unwrap() = dependencies;
 	*file_state.tags.lock().unwrap() = tags;
-
-	handle.emit("hide_spinner", ()).unwrap();
-
-	Ok(())
 }
 
 #[tauri::command]
@@ -204,6 +284,7 @@ pub fn save_file(handle: AppHandle) {
 pub fn save_file_as(handle: AppHandle) {
 	let file_handle = create_file_dialog(&handle)
 		.add_filter("Agents", &["agent", "agents"])
+		.add_filter("Exported Creatures", &["creature"])
 		.save_file();
 	if let Some(file_handle) = file_handle {
 		save_file_to_path(handle, file_handle.as_path());
@@ -212,10 +293,52 @@ pub fn save_file_as(handle: AppHandle) {
 
 fn save_file_to_path(handle: AppHandle, file_path: &Path) {
 	let file_state: State<FileState> = handle.state();
-	*file_state.path.lock().unwrap() = Some(PathBuf::from(file_path));
 	let tags = file_state.tags.lock().unwrap().clone();
 	let dependencies = file_state.dependencies.lock().unwrap().clone();
-	match encode(&tags, &dependencies) {
+
+	let issues = validate(&tags, &dependencies);
+	handle.emit("update_validation_report", &issues).unwrap();
+	if has_errors(&issues) {
+		let config_state: State<ConfigState> = handle.state();
+		if *config_state.block_save_on_errors.lock().unwrap() {
+			error_dialog("This agent has errors that need to be fixed before it can be saved.".to_string());
+			return;
+		}
+		let confirm_save = MessageDialog::new()
+			.set_title("Problems Found")
+			.set_description("This agent has errors that may stop it from working in game.\n\nSave anyway?")
+			.set_buttons(MessageButtons::YesNo)
+			.show();
+		if let MessageDialogResult::No = confirm_save {
+			return;
+		}
+	}
+
+	let encoding_warnings = check_encoding(&tags, &dependencies);
+	if !encoding_warnings.is_empty() {
+		let confirm_save = MessageDialog::new()
+			.set_title("Unsupported Characters")
+			.set_description(format!(
+				"Some text contains characters that the games can't display. They will be saved as \"?\".\n\n{}\n\nSave anyway?",
+				encoding_warnings.join("\n")
+			))
+			.set_buttons(MessageButtons::YesNo)
+			.show();
+		if let MessageDialogResult::No = confirm_save {
+			return;
+		}
+	}
+
+	*file_state.path.lock().unwrap() = Some(PathBuf::from(file_path));
+	let config_state: State<ConfigState> = handle.state();
+	let compression = *config_state.compression.lock().unwrap();
+	let layout = file_state.layout.lock().unwrap().clone();
+	let block_order = file_state.block_order.lock().unwrap().clone();
+	let encoded = match layout {
+		Some(layout) if *config_state.preserve_layout.lock().unwrap() => encode_with_layout(&tags, &dependencies, &compression, &layout, &block_order),
+		_ => encode_in_order(&tags, &dependencies, &compression, &block_order)
+	};
+	match encoded {
 		Ok(bytes) => {
 			match fs::write(file_path, &bytes) {
 				Ok(()) => {
@@ -229,15 +352,122 @@ fn save_file_to_path(handle: AppHandle, file_path: &Path) {
 	}
 }
 
+pub fn show_block_order_dialog(handle: &AppHandle) {
+	let file_state: State<FileState> = handle.state();
+	let tags = file_state.tags.lock().unwrap().clone();
+	let dependencies = file_state.dependencies.lock().unwrap().clone();
+	let order = file_state.block_order.lock().unwrap().clone();
+	// the editor's own order is sent too, so the dialog can go back to it
+	handle.emit("show_block_order_dialog", (block_order(&tags, &dependencies, &order), block_order(&tags, &dependencies, &[]))).unwrap();
+}
+
+// swaps a tag's old id and name in the block order for its new ones
+pub fn update_block_order(file_state: &State<FileState>, old_tag: &Block, new_tag: &Block) {
+	let old_key = old_tag.key();
+	if let Some(key) = file_state.block_order.lock().unwrap().iter_mut().find(|key| **key == old_key) {
+		*key = new_tag.key();
+	}
+}
+
+#[tauri::command]
+pub fn set_block_order(handle: AppHandle, order: Vec<(String, String)>) {
+	let file_state: State<FileState> = handle.state();
+	if *file_state.block_order.lock().unwrap() != order {
+		modify_file(&handle, true);
+		*file_state.block_order.lock().unwrap() = order;
+	}
+}
+
+#[tauri::command]
+pub fn check_file(handle: AppHandle) {
+	let file_state: State<FileState> = handle.state();
+	let tags = file_state.tags.lock().unwrap().clone();
+	let dependencies = file_state.dependencies.lock().unwrap().clone();
+	let issues = validate(&tags, &dependencies);
+	if issues.is_empty() {
+		handle.emit("show_notification", "No problems found").unwrap();
+	}
+	handle.emit("update_validation_report", &issues).unwrap();
+}
+
+#[tauri::command]
+pub fn export_source_file(handle: AppHandle) {
+	let file_state: State<FileState> = handle.state();
+	let file_name = match file_state.path.lock().unwrap().clone() {
+		Some(file_path) => format!("{}.txt", file_path.file_stem().unwrap_or(OsStr::new("")).to_str().unwrap_or("")),
+		None => "Untitled.txt".to_string()
+	};
+	let file_handle = create_file_dialog(&handle)
+		.add_filter("PRAYsource", &["txt"])
+		.set_file_name(file_name)
+		.save_file();
+	if let Some(file_handle) = file_handle {
+		if let Err(why) = export_source_file_to_path(&handle, file_handle.as_path()) {
+			error_dialog(why.to_string());
+		}
+	}
+}
+
+fn export_source_file_to_path(handle: &AppHandle, file_path: &Path) -> Result<(), Box<dyn Error>> {
+	let file_state: State<FileState> = handle.state();
+	let tags = file_state.tags.lock().unwrap().clone();
+	let dependencies = file_state.dependencies.lock().unwrap().clone();
+	let (text, files) = pray_source::encode(&tags, &dependencies)?;
+
+	let root_path = file_path.parent().unwrap_or(Path::new(""));
+	let file_paths = files.iter().map(|f| file_path_in(root_path, &f.filename())).collect::<Result<Vec<PathBuf>, _>>()?;
+	let files_to_overwrite: Vec<String> = files.iter().zip(&file_paths)
+		.filter(|(_, path)| path.try_exists().unwrap_or(false))
+		.map(|(f, _)| f.filename())
+		.collect();
+	if let Some(first_file_to_overwrite) = files_to_overwrite.first() {
+		let result = MessageDialog::new()
+			.set_title(if files_to_overwrite.len() == 1 { "Overwrite File" } else { "Overwrite Files" })
+			.set_description(match files_to_overwrite.len() {
+				1 => format!("Ok to overwrite \"{}\"?", first_file_to_overwrite),
+				2 => format!("Ok to overwrite \"{}\" and 1 other file?", first_file_to_overwrite),
+				n => format!("Ok to overwrite \"{}\" and {} other files?", first_file_to_overwrite, n - 1)
+				})
+			.set_buttons(MessageButtons::YesNo)
+			.show();
+		if let MessageDialogResult::No = result {
+			return Ok(());
+		}
+	}
+
+	fs::write(file_path, cp1252::encode(&text))?;
+	for (file, path) in files.iter().zip(&file_paths) {
+		fs::write(path, &file.data)?;
+	}
+
+	handle.emit("show_notification", "PRAYsource exported").unwrap();
+
+	Ok(())
+}
+
 pub fn drop_file(handle: &AppHandle, paths: &Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
 	if let Some(first_path) = paths.first() {
 		let first_extension = first_path.extension().unwrap_or(OsStr::new("")).to_ascii_lowercase();
-		if first_extension == "agent" || first_extension == "agents" {
+		if first_extension == "agent" || first_extension == "agents" || first_extension == "creature" {
 			check_file_modified(handle.clone(), first_path.clone(), FileModifiedCallback { func: |handle, path| {
 				if let Err(why) = open_file_from_path(&handle, &path) {
 					error_dialog(why.to_string());
 				}
 			}});
+		} else if first_extension == "txt" && pray_source::is_source_text(&pray_source::decode_text(&fs::read(first_path)?)) {
+			check_file_modified(handle.clone(), first_path.clone(), FileModifiedCallback { func: |handle, path| {
+				if let Err(why) = import_source_file_from_path(&handle, &path) {
+					handle.emit("hide_spinner", ()).unwrap();
+					error_dialog(why.to_string());
+				}
+			}});
+		} else if first_extension == "cob" {
+			check_file_modified(handle.clone(), first_path.clone(), FileModifiedCallback { func: |handle, path| {
+				if let Err(why) = import_cob_file_from_path(&handle, &path) {
+					handle.emit("hide_spinner", ()).unwrap();
+					error_dialog(why.to_string());
+				}
+			}});
 		} else {
 			for path in paths {
 				add_dependency_from_path(handle, path.clone())?;
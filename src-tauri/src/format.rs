@@ -1,6 +0,0 @@
-pub mod pray;
-pub mod agent_block;
-pub mod egg_block;
-pub mod gb_block;
-pub mod file_block;
-pub mod generic_block;
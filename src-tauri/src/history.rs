@@ -19,7 +19,8 @@ pub struct HistoryItem {
 	pub is_modified: bool,
 	pub dependencies: Vec<File>,
 	pub tags: Vec<Block>,
-	pub selected_tag: Option<usize>
+	pub selected_tag: Option<usize>,
+	pub block_order: Vec<(String, String)>
 }
 
 pub fn reset_history(handle: &AppHandle) {
@@ -66,7 +67,8 @@ fn get_current_state(handle: &AppHandle) -> HistoryItem {
 	let dependencies = file_state.dependencies.lock().unwrap().clone();
 	let tags = file_state.tags.lock().unwrap().clone();
 	let selected_tag = *file_state.selected_tag.lock().unwrap();
-	HistoryItem { path, is_modified, dependencies, tags, selected_tag }
+	let block_order = file_state.block_order.lock().unwrap().clone();
+	HistoryItem { path, is_modified, dependencies, tags, selected_tag, block_order }
 }
 
 fn set_current_state(handle: &AppHandle, history_item: &HistoryItem) {
@@ -77,6 +79,7 @@ fn set_current_state(handle: &AppHandle, history_item: &HistoryItem) {
 	*file_state.dependencies.lock().unwrap() = history_item.dependencies.clone();
 	*file_state.tags.lock().unwrap() = history_item.tags.clone();
 	*file_state.selected_tag.lock().unwrap() = history_item.selected_tag;
+	*file_state.block_order.lock().unwrap() = history_item.block_order.clone();
 
 	handle.emit("update_dependency_list", &history_item.dependencies).unwrap();
 	handle.emit("update_tag_list", (history_item.selected_tag.unwrap_or(0), &history_item.tags)).unwrap();
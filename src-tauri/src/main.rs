@@ -26,9 +26,9 @@ use tauri::menu::{
 
 use rfd::{ MessageDialog, MessageButtons };
 
+use theist::{ format, sprite };
+
 mod file;
-mod format;
-mod sprite;
 mod tag;
 mod tag_info;
 mod dependency;
@@ -69,6 +69,15 @@ fn main() {
 					&PredefinedMenuItem::separator(handle)?,
 					&MenuItem::with_id(handle, "save", "Save", true, Some("CmdOrCtrl+S"))?,
 					&MenuItem::with_id(handle, "save_as", "Save As", true, Some("CmdOrCtrl+Shift+S"))?,
+					&MenuItem::with_id(handle, "check_file", "Check For Problems", true, Some("CmdOrCtrl+K"))?,
+					&CheckMenuItem::with_id(handle, "block_save_on_errors", "Don't Save Agents With Errors", true, false, None::<&str>)?,
+					&CheckMenuItem::with_id(handle, "preserve_layout", "Keep Unchanged Blocks As They Were", true, false, None::<&str>)?,
+					&MenuItem::with_id(handle, "compression_settings", "Compression Settings", true, None::<&str>)?,
+					&MenuItem::with_id(handle, "block_order", "Block Order", true, None::<&str>)?,
+					&PredefinedMenuItem::separator(handle)?,
+					&MenuItem::with_id(handle, "import_source", "Import PRAYsource", true, None::<&str>)?,
+					&MenuItem::with_id(handle, "export_source", "Export PRAYsource", true, None::<&str>)?,
+					&MenuItem::with_id(handle, "import_cob", "Convert COB To DS Agent", true, None::<&str>)?,
 					&PredefinedMenuItem::separator(handle)?,
 					&MenuItem::with_id(handle, "quit", "Quit", true, Some("CmdOrCtrl+Q"))?,
 				])?,
@@ -78,6 +87,8 @@ fn main() {
 					&MenuItem::with_id(handle, "redo", "Redo", true, Some("CmdOrCtrl+Shift+Z"))?,
 					&PredefinedMenuItem::separator(handle)?,
 					&MenuItem::with_id(handle, "add_tag", "Add Tag", true, Some("CmdOrCtrl+Shift+N"))?,
+					&MenuItem::with_id(handle, "create_sprite", "Create Sprite", true, None::<&str>)?,
+					&MenuItem::with_id(handle, "discover_dependencies", "Find Script Dependencies", true, None::<&str>)?,
 				])?,
 
 				&Submenu::with_id_and_items(handle, "view", "View", true, &[
@@ -105,12 +116,25 @@ fn main() {
 					"open" => file::open_file(handle),
 					"save" => file::save_file(handle),
 					"save_as" => file::save_file_as(handle),
+					"check_file" => file::check_file(handle),
+					"block_save_on_errors" => config::toggle_block_save_on_errors(&handle),
+					"preserve_layout" => config::toggle_preserve_layout(&handle),
+					"compression_settings" => config::show_compression_dialog(&handle),
+					"block_order" => file::show_block_order_dialog(&handle),
+					"import_source" => file::import_source_file(handle),
+					"export_source" => file::export_source_file(handle),
+					"import_cob" => file::import_cob_file(handle),
 					"quit" => try_quit(handle),
 
 					"undo" => history::undo(handle),
 					"redo" => history::redo(handle),
 
 					"add_tag" => handle.emit("show_add_tag_dialog", ()).unwrap(),
+					"create_sprite" => handle.emit("show_create_sprite_dialog", ()).unwrap(),
+					"discover_dependencies" => {
+						let file_state: State<FileState> = handle.state();
+						dependency::discover_dependencies(handle.clone(), file_state);
+					}
 
 					"theme_dark" => config::set_theme(&handle, "dark", false),
 					"theme_light" => config::set_theme(&handle, "light", false),
@@ -130,6 +154,8 @@ fn main() {
 			dependencies: Mutex::new(Vec::new()),
 			tags: Mutex::new(Vec::new()),
 			selected_tag: Mutex::new(None),
+			layout: Mutex::new(None),
+			block_order: Mutex::new(Vec::new()),
 			image_cache: Mutex::new(file::ImageCache::new())
 		})
 
@@ -140,6 +166,9 @@ fn main() {
 
 		.manage(ConfigState {
 			theme: Mutex::new(config::Theme::Dark),
+			block_save_on_errors: Mutex::new(false),
+			preserve_layout: Mutex::new(false),
+			compression: Mutex::new(format::pray::CompressionOptions::default()),
 		})
 
 		.invoke_handler(tauri::generate_handler![
@@ -149,6 +178,13 @@ fn main() {
 			file::open_file,
 			file::save_file,
 			file::save_file_as,
+			file::check_file,
+			file::import_source_file,
+			file::export_source_file,
+			file::import_cob_file,
+			file::set_block_order,
+
+			config::set_compression_options,
 
 			history::undo,
 			history::redo,
@@ -162,8 +198,12 @@ fn main() {
 
 			tag_info::update_prop_str,
 			tag_info::update_prop_int,
+			tag_info::update_history_event,
+			tag_info::update_generic_values,
+			tag_info::update_compression,
 
 			tag_info::generate_remove_script,
+			tag_info::preview_animation,
 
 			tag_info::update_description_language,
 			tag_info::update_description_text,
@@ -176,8 +216,14 @@ fn main() {
 			dependency::remove_dependency,
 			dependency::check_dependency,
 			dependency::select_dependency,
+			dependency::update_catalogue,
+			dependency::update_dependency_compression,
 			dependency::deselect_dependency,
 			dependency::export_dependency,
+			dependency::export_full_background,
+			dependency::export_music_sample,
+			dependency::create_sprite,
+			dependency::discover_dependencies,
 		])
 
 		.on_page_load(|window, _| {
@@ -215,12 +261,62 @@ fn main() {
 			not_found
 		})
 
+		.register_uri_scheme_protocol("getsound", |context, request| {
+			let handle = context.app_handle();
+
+			let not_found = http::Response::builder().body(Vec::new()).unwrap();
+
+			let uri = request.uri().path();
+			let uri_parts:Vec<&str> = uri.split('/').collect();
+			if let Some(filename) = uri_parts.get(2).map(|part| percent_decode(part)) {
+				let file_state: State<file::FileState> = handle.state();
+				let dependencies = file_state.dependencies.lock().unwrap();
+				let sound = dependencies.iter()
+					.find(|d| d.filename() == filename)
+					.and_then(|dependency| match dependency.extension.as_str() {
+						"wav" => Some(dependency.data.clone()),
+						// music samples are picked out by index
+						"mng" => {
+							let sample_index: usize = uri_parts.get(3)?.parse().ok()?;
+							format::mng::decode(&dependency.data).ok()?.sample_wav(sample_index)
+						}
+						_ => None
+					});
+				if let Some(sound) = sound {
+					return http::Response::builder()
+						.header("Content-Type", "audio/wav")
+						.body(sound)
+						.unwrap()
+				}
+			}
+
+			not_found
+		})
+
 		.run(tauri::generate_context!())
 
 		.expect("error while running tauri application");
 
 }
 
+// file names arrive percent-encoded, so names with spaces or accented letters need decoding to be found
+fn percent_decode(text: &str) -> String {
+	let bytes = text.as_bytes();
+	let mut decoded: Vec<u8> = Vec::new();
+	let mut i = 0;
+	while i < bytes.len() {
+		let is_escape = bytes[i] == b'%' && bytes.len() > i + 2 && bytes[i + 1].is_ascii_hexdigit() && bytes[i + 2].is_ascii_hexdigit();
+		if is_escape {
+			decoded.push(u8::from_str_radix(&text[i + 1..i + 3], 16).unwrap_or(b'?'));
+			i += 3;
+		} else {
+			decoded.push(bytes[i]);
+			i += 1;
+		}
+	}
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
 #[tauri::command]
 fn try_quit(handle: AppHandle) {
 	check_file_modified(handle, PathBuf::new(), FileModifiedCallback { func: |handle, _| {
@@ -2,17 +2,22 @@ use tauri::{ Manager, AppHandle, State, Emitter };
 
 use rfd::{ MessageDialog, MessageButtons, MessageDialogResult };
 
-use crate::file::{ FileState, modify_file };
-use crate::format::pray::Block;
+use crate::file::{ FileState, ImageCache, modify_file };
+use crate::format::pray::{ Block, Compression };
 use crate::format::agent_block::{ Agent, GameSupport, Description, Language };
 use crate::format::egg_block::Egg;
 use crate::format::gb_block::GardenBox;
 use crate::dependency::check_dependencies_for_tag;
+use crate::sprite::s16;
 
 #[tauri::command]
 pub fn select_tag(handle: AppHandle, file_state: State<FileState>, selected_tag: u32) {
-	if let Some(tag) = file_state.tags.lock().unwrap().get(selected_tag as usize) {
+	let tags = file_state.tags.lock().unwrap();
+	if let Some(tag) = tags.get(selected_tag as usize) {
 		*file_state.selected_tag.lock().unwrap() = Some(selected_tag as usize);
+		if let Block::Photo(_) | Block::History(_) = tag {
+			cache_photos(&tags, &mut file_state.image_cache.lock().unwrap());
+		}
 		let checked_dependencies = check_dependencies_for_tag(tag, &mut file_state.dependencies.lock().unwrap());
 		handle.emit("update_tag_info", &tag).unwrap();
 		handle.emit("update_checked_dependencies", &checked_dependencies).unwrap();
@@ -20,6 +25,17 @@ pub fn select_tag(handle: AppHandle, file_state: State<FileState>, selected_tag:
 	}
 }
 
+// photos are shown through the image cache like sprites, so a history can show the ones its events mention
+fn cache_photos(tags: &[Block], image_cache: &mut ImageCache) {
+	for tag in tags {
+		if let Block::Photo(photo) = tag {
+			if let Ok(frames) = s16::decode(&photo.data) {
+				image_cache.insert(photo.filename(), frames);
+			}
+		}
+	}
+}
+
 #[tauri::command]
 pub fn add_agent_tag(handle: AppHandle, file_state: State<FileState>) {
 	let new_agent_tag = Block::Agent(Agent {
@@ -34,6 +50,10 @@ pub fn add_agent_tag(handle: AppHandle, file_state: State<FileState>) {
 		sprite_first_image: 0,
 		remove_script: String::new(),
 		dependencies: Vec::new(),
+		extra_int_values: Vec::new(),
+		extra_str_values: Vec::new(),
+		key_order: Vec::new(),
+		compression: Compression::Auto
 	});
 	add_tag(handle, file_state, new_agent_tag);
 }
@@ -49,6 +69,10 @@ pub fn add_egg_tag(handle: AppHandle, file_state: State<FileState>) {
 		sprite_file_female: String::new(),
 		animation_string: String::new(),
 		dependencies: Vec::new(),
+		extra_int_values: Vec::new(),
+		extra_str_values: Vec::new(),
+		key_order: Vec::new(),
+		compression: Compression::Auto
 	});
 	add_tag(handle, file_state, new_egg_tag);
 }
@@ -64,6 +88,10 @@ pub fn add_gb_tag(handle: AppHandle, file_state: State<FileState>) {
 		sprite_first_image: 0,
 		remove_script: String::new(),
 		dependencies: Vec::new(),
+		extra_int_values: Vec::new(),
+		extra_str_values: Vec::new(),
+		key_order: Vec::new(),
+		compression: Compression::Auto
 	});
 	add_tag(handle, file_state, new_gb_tag);
 }
@@ -1,20 +1,33 @@
-use std::str;
-
-use regex::Regex;
-
 use tauri::{ Manager, AppHandle, State, Emitter };
 
 use rfd::{ MessageDialog, MessageButtons, MessageDialogResult };
 
-use crate::file::{ FileState, modify_file };
-use crate::format::pray::Block;
+use crate::error_dialog;
+use crate::file::{ FileState, modify_file, update_block_order };
+use crate::dependency::cache_sprite_frames;
+use crate::format::pray::{ Block, Tag, Compression };
+use crate::format::cp1252;
+use crate::format::caos;
 use crate::format::agent_block::{ GameSupport, Language, Description };
+use crate::format::creature_block::LifeEvent;
+use crate::sprite::animation::{ self, FRAME_DELAY_MS };
+
+#[derive(Clone, serde::Serialize)]
+struct AnimationPreview {
+	filename: String,
+	frames: Vec<usize>,
+	loop_start: Option<usize>,
+	frame_delay: u32,
+	errors: Vec<String>
+}
 
 #[tauri::command]
 pub fn update_prop_str(handle: AppHandle, file_state: State<FileState>, prop: &str, value: &str) {
 	modify_file(&handle, true);
 	if let Some(selected_tag) = *file_state.selected_tag.lock().unwrap() {
 		if let Some(tag) = file_state.tags.lock().unwrap().get_mut(selected_tag) {
+			// renaming a tag or changing its game changes its block, which should stay where it was
+			let old_tag = if prop == "name" || prop == "game_support" { Some(tag.clone()) } else { None };
 			match tag {
 				Block::Agent(agent_tag) => {
 					match prop {
@@ -95,8 +108,35 @@ pub fn update_prop_str(handle: AppHandle, file_state: State<FileState>, prop: &s
 						_ => {}
 					}
 				}
+				Block::Export(export_tag) => {
+					match prop {
+						"creature_name" => {
+							export_tag.creature_name = value.to_string();
+						}
+						"world_name" => {
+							export_tag.world_name = value.to_string();
+						}
+						"world_uid" => {
+							export_tag.world_uid = value.to_string();
+						}
+						_ => {}
+					}
+				}
+				Block::History(history_tag) => {
+					if prop == "creature_name" {
+						history_tag.creature_name = value.to_string();
+					}
+				}
+				Block::Generic(generic_tag) => {
+					if prop == "name" {
+						generic_tag.name = value.to_string();
+					}
+				}
 				_ => {}
 			}
+			if let Some(old_tag) = old_tag {
+				update_block_order(&file_state, &old_tag, tag);
+			}
 		}
 	}
 }
@@ -135,34 +175,68 @@ pub fn update_prop_int(handle: AppHandle, file_state: State<FileState>, prop: &s
 	}
 }
 
+#[tauri::command]
+pub fn update_history_event(handle: AppHandle, file_state: State<FileState>, index: usize, event: LifeEvent) {
+	modify_file(&handle, true);
+	if let Some(selected_tag) = *file_state.selected_tag.lock().unwrap() {
+		if let Some(Block::History(history_tag)) = file_state.tags.lock().unwrap().get_mut(selected_tag) {
+			if let Some(history_event) = history_tag.events.get_mut(index) {
+				*history_event = event;
+			}
+		}
+	}
+}
+
+#[tauri::command]
+pub fn update_generic_values(handle: AppHandle, file_state: State<FileState>, values: Tag) {
+	modify_file(&handle, true);
+	if let Some(selected_tag) = *file_state.selected_tag.lock().unwrap() {
+		if let Some(Block::Generic(generic_tag)) = file_state.tags.lock().unwrap().get_mut(selected_tag) {
+			generic_tag.set_values(values);
+		}
+	}
+}
+
+#[tauri::command]
+pub fn update_compression(handle: AppHandle, file_state: State<FileState>, compression: Compression) {
+	modify_file(&handle, true);
+	if let Some(selected_tag) = *file_state.selected_tag.lock().unwrap() {
+		if let Some(tag) = file_state.tags.lock().unwrap().get_mut(selected_tag) {
+			tag.set_compression(compression);
+		}
+	}
+}
+
 #[tauri::command]
 pub fn generate_remove_script(handle: AppHandle, file_state: State<FileState>) {
 	let mut script_file_name = String::new();
 	let mut remove_script = String::new();
-
-	let remove_script_pattern = Regex::new(r"[\n\r]\s*rscr[\n\r]([\s\S]*)").unwrap();
-	let remove_comments_pattern = Regex::new(r"(?m)^\s+\*.*$").unwrap();
-	let remove_newlines_pattern = Regex::new(r"\s+").unwrap();
+	let mut parse_errors: Vec<String> = Vec::new();
 
 	if let Some(selected_tag) = *file_state.selected_tag.lock().unwrap() {
 		if let Some(Block::Agent(agent_tag)) = file_state.tags.lock().unwrap().get(selected_tag) {
 			for dependency in file_state.dependencies.lock().unwrap().iter() {
 				if &dependency.extension == "cos" && agent_tag.dependencies.contains(&dependency.filename()) {
-					if let Ok(script) = str::from_utf8(&dependency.data) {
-						if let Some(captures) = remove_script_pattern.captures(script) {
-							if let Some(raw_remove_script) = captures.get(1) {
-								remove_script = remove_comments_pattern.replace_all(raw_remove_script.as_str(), " ").to_string();
-								remove_script = remove_newlines_pattern.replace_all(remove_script.as_str(), " ").trim().to_string();
+					let script = cp1252::decode(&dependency.data);
+					match caos::parse(&script) {
+						Ok(caos_file) => {
+							if let Some(raw_remove_script) = caos_file.remove_script {
+								remove_script = raw_remove_script.compact();
 								script_file_name = dependency.filename();
 							}
 						}
+						Err(why) => parse_errors.push(format!("{}: {}", dependency.filename(), why))
 					}
 				}
 			}
 		}
 	}
 
-	if remove_script.is_empty() {
+	// a remove script can't be looked for in a file that can't be read, so say why rather than that there isn't one
+	if remove_script.is_empty() && !parse_errors.is_empty() {
+		error_dialog(format!("Unable to look for a remove script:\n\n{}", parse_errors.join("\n")));
+
+	} else if remove_script.is_empty() {
 		MessageDialog::new()
 			.set_title("Remove Script Not Found")
 			.set_description("No remove script found in any of this tag's COS files.")
@@ -193,6 +267,50 @@ pub fn generate_remove_script(handle: AppHandle, file_state: State<FileState>) {
 	}
 }
 
+#[tauri::command]
+pub fn preview_animation(handle: AppHandle, file_state: State<FileState>) {
+	let selected_tag = *file_state.selected_tag.lock().unwrap();
+	let animation_props = match selected_tag.and_then(|i| file_state.tags.lock().unwrap().get(i).cloned()) {
+		Some(Block::Agent(tag)) => Some((tag.animation_file, tag.animation_string, tag.sprite_first_image)),
+		Some(Block::Egg(tag)) => Some((tag.sprite_file_male, tag.animation_string, 0)),
+		Some(Block::GardenBox(tag)) => Some((tag.animation_file, String::new(), tag.sprite_first_image)),
+		_ => None
+	};
+
+	if let Some((filename, animation_string, first_image)) = animation_props {
+		let mut preview = AnimationPreview {
+			filename: filename.clone(),
+			frames: Vec::new(),
+			loop_start: None,
+			frame_delay: FRAME_DELAY_MS,
+			errors: Vec::new()
+		};
+
+		if !filename.is_empty() {
+			let dependencies = file_state.dependencies.lock().unwrap();
+			let mut image_cache = file_state.image_cache.lock().unwrap();
+			let frame_count = dependencies.iter()
+				.find(|d| d.filename() == filename)
+				.and_then(|d| cache_sprite_frames(d, &mut image_cache));
+			match (frame_count, animation::parse(&animation_string, first_image)) {
+				(Some(frame_count), Ok(animation)) => {
+					preview.errors = animation::check_frame_count(&animation, &filename, frame_count);
+					preview.frames = animation.frames;
+					preview.loop_start = animation.loop_start;
+				}
+				(None, _) => {
+					preview.errors.push(format!("Unable to read images from \"{}\"", filename));
+				}
+				(_, Err(why)) => {
+					preview.errors.push(why.to_string());
+				}
+			}
+		}
+
+		handle.emit("update_animation_preview", preview).unwrap();
+	}
+}
+
 #[tauri::command]
 pub fn update_description_language(handle: AppHandle, file_state: State<FileState>, index: u32, value: &str) {
 	modify_file(&handle, true);
@@ -107,6 +107,7 @@ const updateAgentInfo = (tag) => {
 				</label>
 			</div>`
 	}
+	tagInfoEl.innerHTML += animationPreviewRow()
 	tagInfoEl.innerHTML += `
 		<div class="input-row tall">
 			<span class="label">Remove Script</span>
@@ -141,6 +142,8 @@ const updateAgentInfo = (tag) => {
 	setupPropEvent('sprite_first_image', false)
 	setupPropEvent('remove_script', true)
 
+	setupAnimationPreview(['animation_file', 'animation_string', 'sprite_first_image'])
+
 	document.getElementById('generate-remove-script-button').addEventListener('click', () =>
 		tauri_invoke('generate_remove_script', {})
 	)
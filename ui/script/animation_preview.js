@@ -0,0 +1,68 @@
+let animationPreviewTimer = null
+
+const animationPreviewRow = () => `
+	<div class="input-row tall">
+		<span class="label">Preview</span>
+		<div id="animation-preview" class="animation-preview"></div>
+	</div>`
+
+const setupAnimationPreview = (propNames) => {
+	propNames.forEach(propName => {
+		const el = document.getElementById(`prop-${propName.replace(/_/g, '-')}`)
+		if (el != null) {
+			el.addEventListener('input', () => tauri_invoke('preview_animation'))
+		}
+	})
+	tauri_invoke('preview_animation')
+}
+
+const updateAnimationPreview = (event) => {
+	clearInterval(animationPreviewTimer)
+
+	const previewEl = document.getElementById('animation-preview')
+	if (previewEl == null || event == null || event.payload == null) return
+	previewEl.innerHTML = ''
+
+	const { filename, frames, loop_start, frame_delay, errors } = event.payload
+
+	if (frames.length) {
+		const timestamp = Date.now()
+		const img = document.createElement('img')
+		previewEl.append(img)
+
+		let position = 0
+		const showFrame = () => {
+			img.src = convertFileSrc(`${timestamp}`, 'getimage') + `/${filename}/${frames[position]}`
+		}
+		showFrame()
+
+		if (frames.length > 1) {
+			animationPreviewTimer = setInterval(() => {
+				if (!img.isConnected) {
+					clearInterval(animationPreviewTimer)
+					return
+				}
+				position += 1
+				if (position >= frames.length) {
+					if (loop_start == null) {
+						// animations without R stop on their last image
+						clearInterval(animationPreviewTimer)
+						return
+					}
+					position = loop_start
+				}
+				showFrame()
+			}, frame_delay)
+		}
+
+	} else if (!filename) {
+		previewEl.innerHTML = '<em>No animation file</em>'
+	}
+
+	errors.forEach(error => {
+		const errorEl = document.createElement('div')
+		errorEl.className = 'animation-preview-error'
+		errorEl.innerText = error
+		previewEl.append(errorEl)
+	})
+}
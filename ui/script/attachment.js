@@ -0,0 +1,41 @@
+const attachmentFrames = (attachment, framecount) => {
+	const timestamp = Date.now()
+	const framesEl = document.createElement('div')
+	framesEl.className = 'dependency-contents-frames'
+
+	const lineCount = Math.max(framecount, attachment.points.length)
+	for (let i = 0; i < lineCount; i++) {
+		const frameEl = document.createElement('div')
+		frameEl.className = 'frame attachment-frame'
+		framesEl.append(frameEl)
+
+		const imageEl = document.createElement('div')
+		imageEl.className = 'attachment-image'
+		frameEl.append(imageEl)
+
+		if (attachment.sprite && i < framecount) {
+			const img = document.createElement('img')
+			img.src = convertFileSrc(`${timestamp}`, 'getimage') + `/${attachment.sprite}/${i}`
+			imageEl.append(img)
+		}
+
+		const points = attachment.points[i] || []
+		points.forEach(([x, y], j) => {
+			const pointEl = document.createElement('div')
+			pointEl.className = 'attachment-point'
+			pointEl.style.left = `${x}px`
+			pointEl.style.top = `${y}px`
+			pointEl.title = `Point ${j}: ${x} ${y}`
+			imageEl.append(pointEl)
+		})
+
+		const labelEl = document.createElement('div')
+		labelEl.className = 'attachment-label'
+		labelEl.innerText = points.length ?
+			points.map(([x, y]) => `${x} ${y}`).join('  ') :
+			'(no points)'
+		frameEl.append(labelEl)
+	}
+
+	return framesEl
+}
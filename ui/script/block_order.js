@@ -0,0 +1,80 @@
+class BlockOrderDialog {
+	static order = []
+	static defaultOrder = []
+
+	static isOpen() {
+		return document.getElementById('block-order-dialog').classList.contains('open')
+	}
+
+	static open(event) {
+		const [order, defaultOrder] = event.payload
+		BlockOrderDialog.order = order
+		BlockOrderDialog.defaultOrder = defaultOrder
+		BlockOrderDialog.redraw()
+		document.getElementById('block-order-dialog').classList.add('open')
+		document.getElementById('block-order-ok-button').focus()
+	}
+
+	static close() {
+		document.getElementById('block-order-dialog').classList.remove('open')
+	}
+
+	static move(from, to) {
+		const order = BlockOrderDialog.order
+		if (to >= 0 && to < order.length) {
+			order.splice(to, 0, order.splice(from, 1)[0])
+			BlockOrderDialog.redraw()
+		}
+	}
+
+	static redraw() {
+		const iconButton = (icon, title, className, onClick) => {
+			const button = document.createElement('button')
+			button.title = title
+			button.className = className
+			button.innerHTML = `<img src="library/fluent/${icon}.svg" alt="${title}">`
+			button.addEventListener('click', onClick)
+			return button
+		}
+
+		const listEl = document.getElementById('block-order-list')
+		listEl.innerHTML = ''
+		BlockOrderDialog.order.forEach(([id, name], i) => {
+			const rowEl = document.createElement('div')
+			rowEl.className = 'block-order-row'
+			const idEl = document.createElement('span')
+			idEl.className = 'block-order-id'
+			idEl.innerText = id
+			const nameEl = document.createElement('span')
+			nameEl.className = 'block-order-name'
+			nameEl.innerText = name
+			rowEl.append(
+				idEl,
+				nameEl,
+				iconButton('chevron-down', 'Move Up', 'flipped', () => BlockOrderDialog.move(i, i - 1)),
+				iconButton('chevron-down', 'Move Down', '', () => BlockOrderDialog.move(i, i + 1))
+			)
+			listEl.append(rowEl)
+		})
+	}
+
+	static setup() {
+		document.getElementById('block-order-close-button')
+			.addEventListener('click', BlockOrderDialog.close)
+
+		document.getElementById('block-order-cancel-button')
+			.addEventListener('click', BlockOrderDialog.close)
+
+		document.getElementById('block-order-reset-button').addEventListener('click', () => {
+			BlockOrderDialog.order = BlockOrderDialog.defaultOrder.slice()
+			BlockOrderDialog.redraw()
+		})
+
+		document.getElementById('block-order-ok-button').addEventListener('click', () => {
+			BlockOrderDialog.close()
+			tauri_invoke('set_block_order', { order: BlockOrderDialog.order })
+		})
+
+		tauri_listen('show_block_order_dialog', BlockOrderDialog.open)
+	}
+}
@@ -0,0 +1,126 @@
+const catalogueEditor = (index, catalogue) => {
+	const editorEl = document.createElement('div')
+	editorEl.className = 'catalogue-editor'
+
+	const save = () => tauri_invoke('update_catalogue', { index, catalogue })
+
+	const redraw = () => {
+		editorEl.replaceWith(catalogueEditor(index, catalogue))
+		save()
+	}
+
+	const iconButton = (icon, title, onClick) => {
+		const button = document.createElement('button')
+		button.title = title
+		button.innerHTML = `<img src="library/fluent/${icon}.svg" alt="${title}">`
+		button.addEventListener('click', onClick)
+		return button
+	}
+
+	catalogue.entries.forEach((entry, i) => {
+		const entryEl = document.createElement('div')
+		entryEl.className = 'catalogue-entry'
+
+		const headerEl = document.createElement('div')
+		headerEl.className = 'input-row'
+
+		const typeSelect = document.createElement('select')
+		typeSelect.innerHTML = `
+			<option value="TAG" ${entry.is_array ? '' : 'selected'}>TAG</option>
+			<option value="ARRAY" ${entry.is_array ? 'selected' : ''}>ARRAY</option>`
+		typeSelect.addEventListener('change', () => {
+			entry.is_array = typeSelect.value === 'ARRAY'
+			entry.declared_count = entry.is_array ? entry.strings.length : null
+			save()
+		})
+		const typeEl = document.createElement('div')
+		typeEl.className = 'dropdown-container catalogue-entry-type'
+		const arrowEl = document.createElement('div')
+		arrowEl.className = 'dropdown-arrow'
+		arrowEl.innerHTML = '<img src="library/fluent/chevron-down.svg">'
+		typeEl.append(typeSelect, arrowEl)
+
+		const nameInput = document.createElement('input')
+		nameInput.className = 'fill'
+		nameInput.value = entry.name
+		nameInput.title = 'Tag Name'
+		nameInput.addEventListener('change', () => {
+			entry.name = nameInput.value
+			save()
+		})
+
+		const overrideLabel = document.createElement('label')
+		overrideLabel.className = 'catalogue-entry-override'
+		const overrideCheckbox = document.createElement('input')
+		overrideCheckbox.type = 'checkbox'
+		overrideCheckbox.checked = entry.is_override
+		overrideCheckbox.addEventListener('change', () => {
+			entry.is_override = overrideCheckbox.checked
+			save()
+		})
+		overrideLabel.append(overrideCheckbox, 'Override')
+
+		headerEl.append(typeEl, nameInput, overrideLabel, iconButton('delete', 'Remove Tag', () => {
+			catalogue.entries.splice(i, 1)
+			redraw()
+		}))
+		entryEl.append(headerEl)
+
+		const tableEl = document.createElement('table')
+		tableEl.className = 'catalogue-strings'
+		entry.strings.forEach((string, j) => {
+			const rowEl = document.createElement('tr')
+
+			const indexEl = document.createElement('td')
+			indexEl.className = 'catalogue-string-index'
+			indexEl.innerText = j
+
+			const stringEl = document.createElement('td')
+			const stringInput = document.createElement('input')
+			stringInput.value = string
+			stringInput.addEventListener('change', () => {
+				entry.strings[j] = stringInput.value
+				save()
+			})
+			stringEl.append(stringInput)
+
+			const buttonEl = document.createElement('td')
+			buttonEl.append(iconButton('remove', 'Remove String', () => {
+				entry.strings.splice(j, 1)
+				redraw()
+			}))
+
+			rowEl.append(indexEl, stringEl, buttonEl)
+			tableEl.append(rowEl)
+		})
+		entryEl.append(tableEl)
+
+		const addStringRow = document.createElement('div')
+		addStringRow.className = 'input-row'
+		addStringRow.append(iconButton('add', 'Add String', () => {
+			entry.strings.push('')
+			redraw()
+		}))
+		entryEl.append(addStringRow)
+
+		editorEl.append(entryEl)
+	})
+
+	const addEntryRow = document.createElement('div')
+	addEntryRow.className = 'input-row'
+	addEntryRow.append(iconButton('add-tag', 'Add Tag', () => {
+		catalogue.entries.push({
+			name: '',
+			is_array: false,
+			is_override: false,
+			declared_count: null,
+			strings: [''],
+			comments: [],
+			line: 0
+		})
+		redraw()
+	}))
+	editorEl.append(addEntryRow)
+
+	return editorEl
+}
@@ -0,0 +1,59 @@
+class CompressionDialog {
+	static isOpen() {
+		return document.getElementById('compression-dialog').classList.contains('open')
+	}
+
+	static open(event) {
+		const options = event.payload
+		document.getElementById('compression-tags').checked = options.compress_tags
+		document.getElementById('compression-files').checked = options.compress_files
+		document.getElementById('compression-min-file-size').value = options.min_file_size
+		document.getElementById('compression-level').value = options.level
+		document.getElementById('compression-dialog').classList.add('open')
+		document.getElementById('compression-ok-button').focus()
+	}
+
+	static close() {
+		document.getElementById('compression-dialog').classList.remove('open')
+	}
+
+	static setup() {
+		document.getElementById('compression-close-button')
+			.addEventListener('click', CompressionDialog.close)
+
+		document.getElementById('compression-cancel-button')
+			.addEventListener('click', CompressionDialog.close)
+
+		document.getElementById('compression-ok-button').addEventListener('click', () => {
+			const options = {
+				compress_tags: document.getElementById('compression-tags').checked,
+				compress_files: document.getElementById('compression-files').checked,
+				min_file_size: Math.max(0, parseInt(document.getElementById('compression-min-file-size').value) || 0),
+				level: Math.min(9, Math.max(0, parseInt(document.getElementById('compression-level').value) || 0))
+			}
+			CompressionDialog.close()
+			tauri_invoke('set_compression_options', { options })
+		})
+
+		tauri_listen('show_compression_dialog', CompressionDialog.open)
+	}
+}
+
+// how a single tag or file is stored, where Default leaves it to the compression settings
+const compressionRow = (compression, onChange) => {
+	const rowEl = document.createElement('div')
+	rowEl.className = 'input-row'
+	rowEl.innerHTML = `
+		<label>
+			<span class="label">Compression</span>
+			<select>
+				<option value="Auto" ${compression === 'Auto' ? 'selected' : ''}>Default</option>
+				<option value="Stored" ${compression === 'Stored' ? 'selected' : ''}>Stored</option>
+				<option value="Compressed" ${compression === 'Compressed' ? 'selected' : ''}>Compressed</option>
+			</select>
+			<div class="dropdown-arrow"><img src="library/fluent/chevron-down.svg"></div>
+		</label>`
+	const selectEl = rowEl.querySelector('select')
+	selectEl.addEventListener('change', () => onChange(selectEl.value))
+	return rowEl
+}
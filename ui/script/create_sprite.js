@@ -0,0 +1,35 @@
+class CreateSpriteDialog {
+	static isOpen() {
+		return document.getElementById('create-sprite-dialog').classList.contains('open')
+	}
+
+	static open() {
+		document.getElementById('create-sprite-dialog').classList.add('open')
+		document.getElementById('create-sprite-ok-button').focus()
+	}
+
+	static close() {
+		document.getElementById('create-sprite-dialog').classList.remove('open')
+	}
+
+	static setup() {
+		document.getElementById('create-sprite-button')
+			.addEventListener('click', CreateSpriteDialog.open)
+
+		document.getElementById('create-sprite-close-button')
+			.addEventListener('click', CreateSpriteDialog.close)
+
+		document.getElementById('create-sprite-cancel-button')
+			.addEventListener('click', CreateSpriteDialog.close)
+
+		document.getElementById('create-sprite-ok-button').addEventListener('click', () => {
+			const extension = document.getElementById('create-sprite-extension').value
+			const pixelFormat = document.getElementById('create-sprite-pixel-format').value
+			const dithering = document.getElementById('create-sprite-dithering').value
+			CreateSpriteDialog.close()
+			tauri_invoke('create_sprite', { extension, pixelFormat, dithering })
+		})
+
+		tauri_listen('show_create_sprite_dialog', CreateSpriteDialog.open)
+	}
+}
@@ -0,0 +1,174 @@
+const GENDERS = ['Unknown', 'Male', 'Female']
+const GENERA = ['Unknown', 'Norn', 'Grendel', 'Ettin', 'Geat']
+const LIFE_STAGES = ['Embryo', 'Child', 'Adolescent', 'Youth', 'Adult', 'Old', 'Senile']
+const EVENT_TYPES = [
+	'Conceived', 'Spliced', 'Engineered', 'Hatched', 'Aged', 'Exported', 'Imported', 'Died', 'Became Pregnant',
+	'Made Pregnant', 'Child Born', 'Laid By Mother', 'Laid An Egg', 'Photographed', 'Cloned', 'Clone Source',
+	'Warped Out', 'Warped In'
+]
+
+const nameOf = (names, value) => names[value] != null ? names[value] : `${value}`
+
+const photoFilename = (name) => name.toLowerCase().endsWith('.s16') ? name : `${name}.s16`
+
+const creatureTagHeader = (label, name) => `
+	<div class="input-row">
+		<label>
+			<span class="label">${label}</span>
+			<input value="${name}" disabled>
+		</label>
+		<button id="remove-tag-button" title="Remove Tag">
+			<img src="library/fluent/delete.svg" alt="Remove Tag">
+		</button>
+	</div>`
+
+const readOnlyRow = (label, value) => `
+	<div class="input-row">
+		<label>
+			<span class="label">${label}</span>
+			<input value="${value}" disabled>
+		</label>
+	</div>`
+
+const updateExportInfo = (tag) => {
+	tags[selectedTag] = tag
+
+	const tagInfoEl = document.getElementById('tag-info')
+	tagInfoEl.innerHTML = `
+		${creatureTagHeader('Moniker', tag.name)}
+		<div class="input-row">
+			<label>
+				<span class="label">Creature Name</span>
+				<input id="prop-creature-name" value="${tag.creature_name}">
+			</label>
+		</div>
+		${readOnlyRow('Game', tag.game_support === 'DockingStation' ? 'Docking Station' : 'Creatures 3')}
+		${tag.genome_moniker ? readOnlyRow('Genome Moniker', tag.genome_moniker) : ''}
+		${readOnlyRow('Gender', nameOf(GENDERS, tag.gender))}
+		${readOnlyRow('Genus', nameOf(GENERA, tag.genus))}
+		${readOnlyRow('Variant', tag.variant)}
+		${readOnlyRow('Life Stage', nameOf(LIFE_STAGES, tag.life_stage))}
+		${readOnlyRow('Age In Ticks', tag.age_in_ticks)}
+		${readOnlyRow('Pregnancy Status', tag.pregnancy_status)}
+		<div class="input-row">
+			<label>
+				<span class="label">World Name</span>
+				<input id="prop-world-name" value="${tag.world_name}">
+			</label>
+		</div>
+		<div class="input-row">
+			<label>
+				<span class="label">World UID</span>
+				<input id="prop-world-uid" value="${tag.world_uid}">
+			</label>
+		</div>
+		${tag.extra_int_values.map(([key, value]) => readOnlyRow(key, value)).join('')}
+		${tag.extra_str_values.map(([key, value]) => readOnlyRow(key, value)).join('')}`
+
+	setupPropEvent('creature_name', true)
+	setupPropEvent('world_name', true)
+	setupPropEvent('world_uid', true)
+}
+
+const updateHistoryInfo = (tag) => {
+	tags[selectedTag] = tag
+
+	const tagInfoEl = document.getElementById('tag-info')
+	tagInfoEl.innerHTML = `
+		${creatureTagHeader('Moniker', tag.name)}
+		<div class="input-row">
+			<label>
+				<span class="label">Creature Name</span>
+				<input id="prop-creature-name" value="${tag.creature_name}">
+			</label>
+		</div>
+		${readOnlyRow('Gender', nameOf(GENDERS, tag.gender))}
+		${readOnlyRow('Genus', nameOf(GENERA, tag.genus))}
+		${readOnlyRow('Mutations', tag.mutations)}
+		${readOnlyRow('Crossovers', tag.crossovers)}
+		<div id="history-events" class="history-events"></div>`
+
+	setupPropEvent('creature_name', true)
+
+	const timestamp = Date.now()
+	const eventsEl = document.getElementById('history-events')
+	tag.events.forEach((event, i) => {
+		const eventEl = document.createElement('details')
+		eventEl.className = 'history-event'
+
+		const summaryEl = document.createElement('summary')
+		summaryEl.innerText = `${nameOf(EVENT_TYPES, event.event_type)} (${nameOf(LIFE_STAGES, event.life_stage)})`
+		eventEl.append(summaryEl)
+
+		const headerEl = document.createElement('div')
+		headerEl.className = 'history-event-header'
+		headerEl.innerText = [
+			event.real_time ? new Date(event.real_time * 1000).toLocaleString() : '',
+			`Age ${event.age_in_ticks} ticks`,
+			`World time ${event.world_time}`,
+			[event.moniker_1, event.moniker_2].filter(m => m).join(', ')
+		].filter(s => s).join(' | ')
+		eventEl.append(headerEl)
+
+		const photo = tags.map(t => t.Photo).find(p => p != null && p.name === event.photo)
+		if (photo != null) {
+			const img = document.createElement('img')
+			img.className = 'history-event-photo'
+			img.src = convertFileSrc(`${timestamp}`, 'getimage') + `/${photoFilename(photo.name)}/0`
+			eventEl.append(img)
+		}
+
+		const fields = [['user_text', 'User Text'], ['world_name', 'World Name'], ['world_uid', 'World UID']]
+		if (tag.has_network_fields) {
+			fields.push(['network_user', 'Network User'])
+		}
+		fields.forEach(([prop, label]) => {
+			const rowEl = document.createElement('div')
+			rowEl.className = 'input-row'
+			const labelEl = document.createElement('label')
+			const labelTextEl = document.createElement('span')
+			labelTextEl.className = 'label'
+			labelTextEl.innerText = label
+			const inputEl = document.createElement('input')
+			inputEl.value = event[prop]
+			inputEl.addEventListener('input', () => {
+				event[prop] = inputEl.value
+				tauri_invoke('update_history_event', { index: i, event })
+			})
+			labelEl.append(labelTextEl, inputEl)
+			rowEl.append(labelEl)
+			eventEl.append(rowEl)
+		})
+
+		eventsEl.append(eventEl)
+	})
+}
+
+const updateCreatureDataInfo = (tag) => {
+	tags[selectedTag] = tag
+
+	const tagInfoEl = document.getElementById('tag-info')
+	tagInfoEl.innerHTML = `
+		${creatureTagHeader('Moniker', tag.name)}
+		${readOnlyRow('Size', `${tag.data.length} bytes`)}
+		<div class="input-row">
+			<span class="label"></span>
+			<span>The creature's body and brain as saved by the game, which can only be changed in game.</span>
+		</div>`
+}
+
+const updatePhotoInfo = (tag) => {
+	tags[selectedTag] = tag
+
+	const timestamp = Date.now()
+	const filename = photoFilename(tag.name)
+	const tagInfoEl = document.getElementById('tag-info')
+	tagInfoEl.innerHTML = `
+		${creatureTagHeader('Photo', tag.name)}
+		<div class="input-row">
+			<span class="label"></span>
+			<div class="frame">
+				<img src="${convertFileSrc(`${timestamp}`, 'getimage')}/${filename}/0" alt="${tag.name}">
+			</div>
+		</div>`
+}
@@ -3,7 +3,7 @@ const updateDependencyInfo = (event) => {
 	lastSelected = 0
 
 	if (event && event.payload != null) {
-		const { index, filename, text, framecount } = event.payload
+		const { index, filename, text, framecount, scripts, classifiers, catalogue, genes, attachment, sound, music, compression, errors } = event.payload
 
 		const tagInfoEl = document.getElementById('tag-info')
 		tagInfoEl.innerHTML = ''
@@ -17,16 +17,79 @@ const updateDependencyInfo = (event) => {
 		titleEl.innerHTML = `<span>${filename}</span>`
 		depInfoEl.append(titleEl)
 
+		depInfoEl.append(compressionRow(compression, (compression) =>
+			tauri_invoke('update_dependency_compression', { index, compression })
+		))
+
 		const exportButton = document.createElement('button')
 		exportButton.title = 'Export File'
 		exportButton.innerHTML = '<img src="library/fluent/export.svg" alt="Export File">'
-		exportButton.addEventListener('click', () =>
-			tauri_invoke('export_dependency', { index, selectedFrames })
-		)
+		exportButton.addEventListener('click', () => {
+			if (framecount && !attachment) {
+				ExportSpriteDialog.open(index)
+			} else {
+				tauri_invoke('export_dependency', { index, selectedFrames })
+			}
+		})
 
 		const contentsEl = document.createElement('div')
 
-		if (text) {
+		errors.forEach(error => {
+			const errorEl = document.createElement('div')
+			errorEl.className = 'dependency-error'
+			errorEl.innerText = error
+			depInfoEl.append(errorEl)
+		})
+
+		if (scripts.length) {
+			contentsEl.className = 'dependency-contents-scripts'
+			if (classifiers.length) {
+				const classifiersEl = document.createElement('div')
+				classifiersEl.className = 'dependency-classifiers'
+				classifiers.forEach(classifier => {
+					const classifierEl = document.createElement('div')
+					classifierEl.innerText = classifier
+					classifiersEl.append(classifierEl)
+				})
+				contentsEl.append(classifiersEl)
+			}
+			scripts.forEach(script => {
+				const scriptTitleEl = document.createElement('div')
+				scriptTitleEl.className = 'script-title'
+				scriptTitleEl.innerText = script.start_line === script.end_line ?
+					`${script.title} (line ${script.start_line})` :
+					`${script.title} (lines ${script.start_line}-${script.end_line})`
+				const scriptTextEl = document.createElement('div')
+				scriptTextEl.className = 'dependency-contents-text'
+				scriptTextEl.innerText = script.text
+				contentsEl.append(scriptTitleEl, scriptTextEl)
+			})
+			titleEl.append(exportButton)
+
+		} else if (catalogue) {
+			contentsEl.className = 'dependency-contents-catalogue'
+			contentsEl.append(catalogueEditor(index, catalogue))
+			titleEl.append(exportButton)
+
+		} else if (genes.length) {
+			contentsEl.className = 'dependency-contents-genes'
+			contentsEl.append(geneList(genes))
+			titleEl.append(exportButton)
+
+		} else if (attachment) {
+			contentsEl.append(attachmentFrames(attachment, framecount))
+			titleEl.append(exportButton)
+
+		} else if (music) {
+			contentsEl.className = 'dependency-contents-scripts'
+			contentsEl.append(musicPreview(index, filename, music, text))
+			titleEl.append(exportButton)
+
+		} else if (sound) {
+			contentsEl.append(soundPreview(filename, sound))
+			titleEl.append(exportButton)
+
+		} else if (text) {
 			contentsEl.className = 'dependency-contents-text'
 			contentsEl.innerHTML = text
 			titleEl.append(exportButton)
@@ -46,6 +109,16 @@ const updateDependencyInfo = (event) => {
 			})
 			titleEl.append(exportButton)
 
+			if (filename.toLowerCase().endsWith('.blk')) {
+				const exportBackgroundButton = document.createElement('button')
+				exportBackgroundButton.title = 'Export Full Background'
+				exportBackgroundButton.innerHTML = '<img src="library/fluent/image.svg" alt="Export Full Background">'
+				exportBackgroundButton.addEventListener('click', () =>
+					tauri_invoke('export_full_background', { index })
+				)
+				titleEl.append(exportBackgroundButton)
+			}
+
 		} else {
 			contentsEl.className = 'dependency-contents-invalid'
 			contentsEl.innerHTML = '<em>No preview available</em>'
@@ -85,7 +85,8 @@ const updateEggInfo = (tag) => {
 				<span class="label">Animation String</span>
 				<input id="prop-animation-string" value="${tag.animation_string}">
 			</label>
-		</div>`
+		</div>
+		${animationPreviewRow()}`
 
 	document.getElementById('prop-name').addEventListener('input', (event) => {
 		if (event.target != null) {
@@ -108,4 +109,6 @@ const updateEggInfo = (tag) => {
 	setupPropEvent('sprite_file_male', true)
 	setupPropEvent('sprite_file_female', true)
 	setupPropEvent('animation_string', true)
+
+	setupAnimationPreview(['sprite_file_male', 'animation_string'])
 }
@@ -0,0 +1,47 @@
+class ExportSpriteDialog {
+	static index = 0
+
+	static isOpen() {
+		return document.getElementById('export-sprite-dialog').classList.contains('open')
+	}
+
+	static open(index) {
+		ExportSpriteDialog.index = index
+		ExportSpriteDialog.updateFrameDelay()
+		document.getElementById('export-sprite-dialog').classList.add('open')
+		document.getElementById('export-sprite-ok-button').focus()
+	}
+
+	static close() {
+		document.getElementById('export-sprite-dialog').classList.remove('open')
+	}
+
+	static updateFrameDelay() {
+		const spriteFormat = document.getElementById('export-sprite-format').value
+		const frameDelayEl = document.getElementById('export-sprite-frame-delay')
+		if (spriteFormat === 'Gif' || spriteFormat === 'Apng') {
+			frameDelayEl.removeAttribute('disabled')
+		} else {
+			frameDelayEl.setAttribute('disabled', 'disabled')
+		}
+	}
+
+	static setup() {
+		document.getElementById('export-sprite-close-button')
+			.addEventListener('click', ExportSpriteDialog.close)
+
+		document.getElementById('export-sprite-cancel-button')
+			.addEventListener('click', ExportSpriteDialog.close)
+
+		document.getElementById('export-sprite-format')
+			.addEventListener('input', ExportSpriteDialog.updateFrameDelay)
+
+		document.getElementById('export-sprite-ok-button').addEventListener('click', () => {
+			const index = ExportSpriteDialog.index
+			const spriteFormat = document.getElementById('export-sprite-format').value
+			const frameDelay = Math.max(10, parseInt(document.getElementById('export-sprite-frame-delay').value) || 50)
+			ExportSpriteDialog.close()
+			tauri_invoke('export_dependency', { index, selectedFrames, spriteFormat, frameDelay })
+		})
+	}
+}
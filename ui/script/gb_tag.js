@@ -50,6 +50,7 @@ const updateGardenBoxInfo = (tag) => {
 				<input id="prop-sprite-first-image" type="number" step="1" min="0" value="${tag.sprite_first_image}">
 			</label>
 		</div>
+		${animationPreviewRow()}
 		<div class="input-row">
 			<label>
 				<span class="label">Description</span>
@@ -93,6 +94,8 @@ const updateGardenBoxInfo = (tag) => {
 	setupPropEvent('author', true)
 	setupPropEvent('remove_script', true)
 
+	setupAnimationPreview(['animation_file', 'sprite_first_image'])
+
 	document.getElementById('generate-remove-script-button').addEventListener('click', () => {
 		tauri_invoke('generate_remove_script', {})
 	})
@@ -0,0 +1,65 @@
+const geneList = (genes) => {
+	const listEl = document.createElement('div')
+	listEl.className = 'gene-list'
+
+	const filterInput = document.createElement('input')
+	filterInput.className = 'gene-filter'
+	filterInput.placeholder = 'Filter Genes'
+	listEl.append(filterInput)
+
+	const geneEls = genes.map((gene, i) => {
+		const geneEl = document.createElement('details')
+		geneEl.className = 'gene'
+
+		const summaryEl = document.createElement('summary')
+		summaryEl.innerText = gene.caption ?
+			`${i + 1}. ${gene.title}: ${gene.caption}` :
+			`${i + 1}. ${gene.title}`
+		geneEl.append(summaryEl)
+
+		const headerEl = document.createElement('div')
+		headerEl.className = 'gene-header'
+		headerEl.innerText = [
+			`ID ${gene.id}`,
+			`Generation ${gene.generation}`,
+			`Switch On: ${gene.life_stage}`,
+			`Mutability ${gene.mutability}`,
+			`Variant ${gene.variant}`,
+			gene.flags.join(', ')
+		].filter(s => s).join(' | ')
+		geneEl.append(headerEl)
+
+		if (gene.note) {
+			const noteEl = document.createElement('div')
+			noteEl.className = 'gene-note'
+			noteEl.innerText = gene.note
+			geneEl.append(noteEl)
+		}
+
+		const tableEl = document.createElement('table')
+		tableEl.className = 'gene-fields'
+		gene.fields.forEach(([name, value]) => {
+			const rowEl = document.createElement('tr')
+			const nameEl = document.createElement('td')
+			nameEl.innerText = name
+			const valueEl = document.createElement('td')
+			valueEl.innerText = value
+			rowEl.append(nameEl, valueEl)
+			tableEl.append(rowEl)
+		})
+		geneEl.append(tableEl)
+
+		listEl.append(geneEl)
+		return geneEl
+	})
+
+	filterInput.addEventListener('input', () => {
+		const filter = filterInput.value.toLowerCase()
+		geneEls.forEach(geneEl => {
+			const matches = geneEl.innerText.toLowerCase().includes(filter)
+			geneEl.classList.toggle('hidden', !matches)
+		})
+	})
+
+	return listEl
+}
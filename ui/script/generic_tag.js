@@ -0,0 +1,165 @@
+const HEX_VIEW_LIMIT = 65536
+
+const updateGenericInfo = (tag) => {
+	tags[selectedTag] = tag
+
+	const tagInfoEl = document.getElementById('tag-info')
+	tagInfoEl.innerHTML = `
+		<div class="input-row">
+			<label>
+				<span class="label">${tag.id} Tag Name</span>
+				<input id="prop-name" value="${tag.name}">
+			</label>
+			<button id="duplicate-tag-button" title="Duplicate Tag">
+				<img src="library/fluent/duplicate.svg" alt="Duplicate Tag">
+			</button>
+			<button id="remove-tag-button" title="Remove Tag">
+				<img src="library/fluent/delete.svg" alt="Remove Tag">
+			</button>
+		</div>
+		<div id="generic-contents" class="generic-contents"></div>`
+
+	document.getElementById('prop-name').addEventListener('input', (event) => {
+		if (event.target != null) {
+			tag.name = event.target.value
+			tauri_invoke('update_prop_str', {
+				prop: 'name',
+				value: event.target.value
+			})
+			tab = document.getElementById(`tag-${selectedTag}`)
+			if (tab != null) {
+				tab.innerText = event.target.value
+				tab.title = event.target.value
+			}
+		}
+	})
+
+	const contentsEl = document.getElementById('generic-contents')
+	if (tag.values != null) {
+		const rows = [
+			...tag.values.int_values.map(([key, value]) => ({ key, isInt: true, value: `${value}` })),
+			...tag.values.str_values.map(([key, value]) => ({ key, isInt: false, value }))
+		]
+		contentsEl.append(tagValueEditor(tag, rows))
+	} else {
+		contentsEl.append(hexView(tag.data))
+	}
+}
+
+const tagValueEditor = (tag, rows) => {
+	const editorEl = document.createElement('div')
+	editorEl.className = 'tag-value-editor'
+
+	const isValidInt = (value) => /^\d+$/.test(value) && parseInt(value) <= 4294967295
+
+	// values are only sent once every number is valid, so a half-typed one doesn't get saved
+	const save = () => {
+		if (rows.every(row => !row.isInt || isValidInt(row.value))) {
+			tag.values = {
+				int_values: rows.filter(row => row.isInt).map(row => [row.key, parseInt(row.value)]),
+				str_values: rows.filter(row => !row.isInt).map(row => [row.key, row.value])
+			}
+			tauri_invoke('update_generic_values', { values: tag.values })
+		}
+	}
+
+	const redraw = () => {
+		editorEl.replaceWith(tagValueEditor(tag, rows))
+		save()
+	}
+
+	const iconButton = (icon, title, onClick) => {
+		const button = document.createElement('button')
+		button.title = title
+		button.innerHTML = `<img src="library/fluent/${icon}.svg" alt="${title}">`
+		button.addEventListener('click', onClick)
+		return button
+	}
+
+	const tableEl = document.createElement('table')
+	tableEl.className = 'tag-values'
+	rows.forEach((row, i) => {
+		const rowEl = document.createElement('tr')
+
+		const keyEl = document.createElement('td')
+		const keyInput = document.createElement('input')
+		keyInput.value = row.key
+		keyInput.title = 'Key'
+		keyInput.addEventListener('change', () => {
+			row.key = keyInput.value
+			save()
+		})
+		keyEl.append(keyInput)
+
+		const typeEl = document.createElement('td')
+		const typeContainer = document.createElement('div')
+		typeContainer.className = 'dropdown-container tag-value-type'
+		const typeSelect = document.createElement('select')
+		typeSelect.innerHTML = `
+			<option value="int" ${row.isInt ? 'selected' : ''}>Number</option>
+			<option value="str" ${row.isInt ? '' : 'selected'}>Text</option>`
+		const arrowEl = document.createElement('div')
+		arrowEl.className = 'dropdown-arrow'
+		arrowEl.innerHTML = '<img src="library/fluent/chevron-down.svg">'
+		typeContainer.append(typeSelect, arrowEl)
+		typeEl.append(typeContainer)
+
+		const valueEl = document.createElement('td')
+		const valueInput = document.createElement('input')
+		valueInput.value = row.value
+		valueInput.title = 'Value'
+		const checkValue = () => valueInput.classList.toggle('invalid', row.isInt && !isValidInt(row.value))
+		checkValue()
+		valueInput.addEventListener('input', () => {
+			row.value = valueInput.value
+			checkValue()
+			save()
+		})
+		typeSelect.addEventListener('change', () => {
+			row.isInt = typeSelect.value === 'int'
+			checkValue()
+			save()
+		})
+		valueEl.append(valueInput)
+
+		const buttonEl = document.createElement('td')
+		buttonEl.append(iconButton('remove', 'Remove Value', () => {
+			rows.splice(i, 1)
+			redraw()
+		}))
+
+		rowEl.append(keyEl, typeEl, valueEl, buttonEl)
+		tableEl.append(rowEl)
+	})
+	editorEl.append(tableEl)
+
+	const addRow = document.createElement('div')
+	addRow.className = 'input-row'
+	addRow.append(iconButton('add', 'Add Value', () => {
+		rows.push({ key: '', isInt: false, value: '' })
+		redraw()
+	}))
+	editorEl.append(addRow)
+
+	return editorEl
+}
+
+// offset, sixteen bytes in hex, then the same bytes as text
+const hexView = (data) => {
+	const viewEl = document.createElement('div')
+	viewEl.className = 'hex-view'
+
+	const lines = []
+	for (let offset = 0; offset < Math.min(data.length, HEX_VIEW_LIMIT); offset += 16) {
+		const bytes = data.slice(offset, offset + 16)
+		const hex = bytes.map(b => b.toString(16).padStart(2, '0')).join(' ').padEnd(47, ' ')
+		const text = bytes.map(b => b >= 32 && b < 127 ? String.fromCharCode(b) : '.').join('')
+		lines.push(`${offset.toString(16).padStart(8, '0')}  ${hex}  ${text}`)
+	}
+	if (data.length > HEX_VIEW_LIMIT) {
+		lines.push(`... showing the first ${HEX_VIEW_LIMIT} of ${data.length} bytes`)
+	}
+	viewEl.innerText = lines.length ? lines.join('\n') : '(empty)'
+
+	return viewEl
+}
@@ -38,6 +38,10 @@ window.addEventListener('load', () => {
 		tauri_invoke('save_file_as')
 	)
 
+	document.getElementById('check-file-button').addEventListener('click', () =>
+		tauri_invoke('check_file')
+	)
+
 	document.getElementById('undo-button').addEventListener('click', () =>
 		tauri_invoke('undo')
 	)
@@ -50,6 +54,10 @@ window.addEventListener('load', () => {
 		tauri_invoke('add_dependency')
 	)
 
+	document.getElementById('discover-dependencies-button').addEventListener('click', () =>
+		tauri_invoke('discover_dependencies')
+	)
+
 	document.getElementById('extract-dependency-button').addEventListener('click', () =>
 		tauri_invoke('extract_dependency', { selectedDependencies })
 	)
@@ -64,6 +72,7 @@ window.addEventListener('load', () => {
 
 	tauri_listen('update_tag_list', updateTagList)
 	tauri_listen('update_tag_info', updateTagInfo)
+	tauri_listen('update_animation_preview', updateAnimationPreview)
 
 	tauri_listen('update_dependency_list', updateDependencyList)
 	tauri_listen('update_checked_dependencies', updateCheckedDependencies)
@@ -103,6 +112,10 @@ window.addEventListener('load', () => {
 			event.preventDefault()
 			tauri_invoke('save_as_file')
 
+		} else if (CTRL && KEY === 'K') {
+			event.preventDefault()
+			tauri_invoke('check_file')
+
 		} else if (CTRL && KEY === 'Z') {
 			event.preventDefault()
 			tauri_invoke('undo')
@@ -135,8 +148,13 @@ window.addEventListener('load', () => {
 				deselectAllDependencies()
 			}
 
-		} else if (KEY === 'ESCAPE' && (AddTagDialog.isOpen() || AboutDialog.isOpen())){
+		} else if (KEY === 'ESCAPE' && (AddTagDialog.isOpen() || CreateSpriteDialog.isOpen() || ExportSpriteDialog.isOpen() || CompressionDialog.isOpen() || BlockOrderDialog.isOpen() || ValidationDialog.isOpen() || AboutDialog.isOpen())){
 			AddTagDialog.close()
+			CreateSpriteDialog.close()
+			ExportSpriteDialog.close()
+			CompressionDialog.close()
+			BlockOrderDialog.close()
+			ValidationDialog.close()
 			AboutDialog.close()
 		} else if (KEY === 'ESCAPE' && selectedDependencies.length) {
 			hideSpinner()
@@ -145,6 +163,11 @@ window.addEventListener('load', () => {
 	})
 
 	AddTagDialog.setup()
+	CreateSpriteDialog.setup()
+	ExportSpriteDialog.setup()
+	CompressionDialog.setup()
+	BlockOrderDialog.setup()
+	ValidationDialog.setup()
 	AboutDialog.setup()
 })
 
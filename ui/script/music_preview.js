@@ -0,0 +1,51 @@
+const musicPreview = (index, filename, music, script) => {
+	const timestamp = Date.now()
+	const previewEl = document.createElement('div')
+	previewEl.className = 'music-preview'
+
+	if (music.tracks.length) {
+		const tracksEl = document.createElement('div')
+		tracksEl.className = 'script-title'
+		tracksEl.innerText = `Tracks: ${music.tracks.join(', ')}`
+		previewEl.append(tracksEl)
+	}
+
+	music.samples.forEach((sample, i) => {
+		const sampleEl = document.createElement('div')
+		sampleEl.className = 'music-sample'
+
+		const nameEl = document.createElement('span')
+		nameEl.className = 'music-sample-name'
+		nameEl.innerText = sample.name
+		nameEl.title = sample.sound ?
+			`${sample.sound.sample_rate} Hz, ${sample.sound.bits_per_sample}-bit, ${(sample.sound.duration_ms / 1000).toFixed(2)} s` :
+			'Unable to read sample'
+		sampleEl.append(nameEl)
+
+		const audioEl = document.createElement('audio')
+		audioEl.controls = true
+		audioEl.preload = 'none'
+		audioEl.src = convertFileSrc(`${timestamp}`, 'getsound') + `/${encodeURIComponent(filename)}/${i}`
+		sampleEl.append(audioEl)
+
+		const exportButton = document.createElement('button')
+		exportButton.title = 'Export Sample'
+		exportButton.innerHTML = '<img src="library/fluent/export.svg" alt="Export Sample">'
+		exportButton.addEventListener('click', () =>
+			tauri_invoke('export_music_sample', { index, sample: i })
+		)
+		sampleEl.append(exportButton)
+
+		previewEl.append(sampleEl)
+	})
+
+	const scriptTitleEl = document.createElement('div')
+	scriptTitleEl.className = 'script-title'
+	scriptTitleEl.innerText = 'Script'
+	const scriptEl = document.createElement('div')
+	scriptEl.className = 'dependency-contents-text'
+	scriptEl.innerText = script
+	previewEl.append(scriptTitleEl, scriptEl)
+
+	return previewEl
+}
@@ -0,0 +1,34 @@
+const soundPreview = (filename, sound) => {
+	const previewEl = document.createElement('div')
+	previewEl.className = 'sound-preview'
+
+	const audioEl = document.createElement('audio')
+	audioEl.controls = true
+	audioEl.src = convertFileSrc(`${Date.now()}`, 'getsound') + `/${encodeURIComponent(filename)}`
+	previewEl.append(audioEl)
+
+	const formatName = sound.format_tag === 1 ? 'PCM' : `Format ${sound.format_tag}`
+	const channelName = sound.channels === 1 ? 'Mono' : sound.channels === 2 ? 'Stereo' : `${sound.channels} channels`
+	const details = [
+		['Format', formatName],
+		['Channels', channelName],
+		['Sample Rate', `${sound.sample_rate} Hz`],
+		['Bit Depth', `${sound.bits_per_sample}-bit`],
+		['Duration', `${(sound.duration_ms / 1000).toFixed(2)} s`]
+	]
+
+	const tableEl = document.createElement('table')
+	tableEl.className = 'sound-details'
+	details.forEach(([name, value]) => {
+		const rowEl = document.createElement('tr')
+		const nameEl = document.createElement('td')
+		nameEl.innerText = name
+		const valueEl = document.createElement('td')
+		valueEl.innerText = value
+		rowEl.append(nameEl, valueEl)
+		tableEl.append(rowEl)
+	})
+	previewEl.append(tableEl)
+
+	return previewEl
+}
@@ -9,11 +9,34 @@ const updateTagInfo = (event) => {
 		} else if (event.payload.GardenBox != null) {
 			updateGardenBoxInfo(event.payload.GardenBox)
 
+		} else if (event.payload.Export != null) {
+			updateExportInfo(event.payload.Export)
+
+		} else if (event.payload.History != null) {
+			updateHistoryInfo(event.payload.History)
+
+		} else if (event.payload.CreatureData != null) {
+			updateCreatureDataInfo(event.payload.CreatureData)
+
+		} else if (event.payload.Photo != null) {
+			updatePhotoInfo(event.payload.Photo)
+
+		} else if (event.payload.Generic != null) {
+			updateGenericInfo(event.payload.Generic)
+
 		} else {
 			const tagInfoEl = document.getElementById('tag-info')
 			tagInfoEl.innerHTML = 'Unknown tag type'
 		}
 
+		const tag = tags[selectedTag]
+		if (tag != null && tag.compression != null) {
+			document.getElementById('tag-info').append(compressionRow(tag.compression, (compression) => {
+				tag.compression = compression
+				tauri_invoke('update_compression', { compression })
+			}))
+		}
+
 		const duplicateTagButton = document.getElementById('duplicate-tag-button')
 		if (duplicateTagButton != null) {
 			duplicateTagButton.addEventListener('click', () => {
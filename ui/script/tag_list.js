@@ -25,6 +25,18 @@ const updateTagList = (event) => {
 			} else if (tag.GardenBox != null) {
 				tagItem.innerText = tag.GardenBox.name
 				tagItem.title = tag.GardenBox.name
+			} else if (tag.Export != null) {
+				tagItem.innerText = tag.Export.creature_name || tag.Export.name
+				tagItem.title = `Exported creature ${tag.Export.name}`
+			} else if (tag.History != null) {
+				tagItem.innerText = `History: ${tag.History.creature_name || tag.History.name}`
+				tagItem.title = tag.History.name
+			} else if (tag.CreatureData != null) {
+				tagItem.innerText = `Creature: ${tag.CreatureData.name}`
+				tagItem.title = tag.CreatureData.name
+			} else if (tag.Photo != null) {
+				tagItem.innerText = `Photo: ${tag.Photo.name}`
+				tagItem.title = tag.Photo.name
 			} else if (tag.Generic != null) {
 				tagItem.innerText = tag.Generic.name
 				tagItem.title = tag.Generic.name
@@ -0,0 +1,52 @@
+class ValidationDialog {
+	static isOpen() {
+		return document.getElementById('validation-dialog').classList.contains('open')
+	}
+
+	static open() {
+		document.getElementById('validation-dialog').classList.add('open')
+		document.getElementById('validation-ok-button').focus()
+	}
+
+	static close() {
+		document.getElementById('validation-dialog').classList.remove('open')
+	}
+
+	static update(event) {
+		const issues = event.payload
+		const listEl = document.getElementById('validation-list')
+		listEl.innerHTML = ''
+
+		for (const severity of ['Error', 'Warning']) {
+			issues.filter(issue => issue.severity === severity).forEach(issue => {
+				const issueEl = document.createElement('div')
+				issueEl.className = `validation-issue ${severity.toLowerCase()}`
+
+				const locationEl = document.createElement('div')
+				locationEl.className = 'validation-location'
+				locationEl.innerText = [severity, issue.tag, issue.field].filter(s => s).join(' - ')
+				issueEl.append(locationEl)
+
+				const messageEl = document.createElement('div')
+				messageEl.innerText = issue.message
+				issueEl.append(messageEl)
+
+				listEl.append(issueEl)
+			})
+		}
+
+		if (issues.length) {
+			ValidationDialog.open()
+		}
+	}
+
+	static setup() {
+		document.getElementById('validation-close-button')
+			.addEventListener('click', ValidationDialog.close)
+
+		document.getElementById('validation-ok-button')
+			.addEventListener('click', ValidationDialog.close)
+
+		tauri_listen('update_validation_report', ValidationDialog.update)
+	}
+}